@@ -0,0 +1,139 @@
+use alloy_primitives::B256;
+use anyhow::{bail, ensure, Context, Result};
+use common::merkle::{find_duplicate, merkle_root};
+use methods_aggregate::AGGREGATE_TEAMS_ELF;
+use methods_team::MAKE_TEAM_ID;
+use risc0_zkvm::{InnerReceipt, ProverOpts, Receipt};
+
+use crate::prover::{ProofRequest, ProverConfig};
+
+/// Proves `aggregate_teams` over `make_team` receipts with `prover`, producing one receipt whose
+/// journal commits to the team count and the Merkle root of the individual team journals.
+///
+/// Journals are committed in the order of `receipts`, so the same teams given in a different
+/// order produce a different root, and a team given twice is rejected. The team receipts are resolved as assumptions, so they must be
+/// succinct rather than Groth16; the aggregate receipt itself is Groth16 for on-chain use.
+pub fn aggregate_team_proofs(prover: &ProverConfig, receipts: &[Receipt]) -> Result<Receipt> {
+    ensure!(!receipts.is_empty(), "no team receipts to aggregate");
+    let journals: Vec<&[u8]> = receipts
+        .iter()
+        .map(|receipt| receipt.journal.bytes.as_slice())
+        .collect();
+    if let Some((first, second)) = find_duplicate(&journals) {
+        bail!("team receipts {first} and {second} commit the same journal");
+    }
+
+    for (index, receipt) in receipts.iter().enumerate() {
+        receipt
+            .verify(MAKE_TEAM_ID)
+            .with_context(|| format!("team receipt {index} is not a valid make_team proof"))?;
+        ensure!(
+            !matches!(receipt.inner, InnerReceipt::Groth16(_)),
+            "team receipt {index} is a Groth16 receipt, which cannot be aggregated"
+        );
+    }
+
+    prover
+        .prove(
+            aggregate_request(receipts)?,
+            AGGREGATE_TEAMS_ELF,
+            &ProverOpts::groth16(),
+        )
+        .context("failed to aggregate team proofs")
+}
+
+/// Input of the `aggregate_teams` guest: the team journals, with their receipts as assumptions
+fn aggregate_request(receipts: &[Receipt]) -> Result<ProofRequest> {
+    let journals: Vec<Vec<u8>> = receipts
+        .iter()
+        .map(|receipt| receipt.journal.bytes.clone())
+        .collect();
+
+    let mut request = ProofRequest::default();
+    request.write(&journals)?;
+    for receipt in receipts {
+        request.add_assumption(receipt.clone());
+    }
+    Ok(request)
+}
+
+/// Computes the root an `aggregate_teams` receipt is expected to commit to for `receipts`
+pub fn expected_root(receipts: &[Receipt]) -> B256 {
+    let journals: Vec<&[u8]> = receipts
+        .iter()
+        .map(|receipt| receipt.journal.bytes.as_slice())
+        .collect();
+    merkle_root(&journals)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use alloy_sol_types::SolValue;
+    use common::fixtures;
+    use risc0_steel::Commitment;
+    use risc0_zkvm::ExecutorImpl;
+
+    use super::*;
+    use crate::journal::{AggregateJournal, TeamJournal};
+    use crate::testing::fake_receipt;
+
+    /// Builds a fake `make_team` receipt whose journal differs per `seed`
+    fn fake_team_receipt(seed: u8) -> Receipt {
+        let journal = TeamJournal {
+            commitment: fixtures::commitment(),
+            teamCID: B256::repeat_byte(seed),
+            playerIds: [U256::from(seed); 11],
+            playerContracts: [Address::repeat_byte(0x66); 11],
+            playerCommitments: std::array::from_fn(|_| Commitment {
+                id: U256::from(6_800_120),
+                ..fixtures::commitment()
+            }),
+            registryHash: B256::repeat_byte(seed.wrapping_add(1)),
+            minRating: 0,
+            maxRating: 100,
+            averageRating: 8000,
+            minTimepoint: 6_800_120,
+            maxTimepoint: 6_800_123,
+            format: 0,
+        }
+        .abi_encode();
+        fake_receipt(MAKE_TEAM_ID, journal)
+    }
+
+    #[test]
+    fn test_aggregate_root_matches_local_root() {
+        let receipts: Vec<Receipt> = (1..=3).map(fake_team_receipt).collect();
+
+        // Executing the guest resolves `env::verify` against the assumptions without proving, so
+        // the test needs neither dev mode nor a prover
+        let env = aggregate_request(&receipts)
+            .unwrap()
+            .executor_env()
+            .unwrap();
+        let session = ExecutorImpl::from_elf(env, AGGREGATE_TEAMS_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        let journal = session.journal.unwrap();
+        let journal = AggregateJournal::abi_decode(&journal.bytes, true).unwrap();
+
+        assert_eq!(journal.teamCount, U256::from(receipts.len()));
+        assert_eq!(journal.root, expected_root(&receipts));
+    }
+
+    #[test]
+    fn test_rejects_empty_batch() {
+        assert!(aggregate_team_proofs(&ProverConfig::Default, &[]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_team() {
+        let receipts = [1, 2, 1].map(fake_team_receipt);
+        let err = aggregate_team_proofs(&ProverConfig::Default, &receipts).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "team receipts 0 and 2 commit the same journal"
+        );
+    }
+}
@@ -16,19 +16,37 @@
 // to the Bonsai proving service and publish the received proofs directly
 // to your deployed app contract.
 
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use alloy::{
-    network::EthereumWallet, providers::ProviderBuilder, signers::local::PrivateKeySigner,
+    network::EthereumWallet,
+    providers::{Provider, ProviderBuilder},
+    signers::local::PrivateKeySigner,
     sol_types::SolValue,
 };
-use alloy_primitives::{Address, U256};
-use anyhow::{Context, Result};
-use clap::Parser;
-use common::cid::{Attribute, ComputeCid, Player, Skill};
-use array_init::array_init;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use anyhow::{anyhow, bail, Context, Result};
+use apps::{
+    commitment::{commitment_point, distinct_timepoints, CommitmentReport},
+    journal::{TeamJournal, VerifyJournal},
+    output::{encode_receipt, Output},
+    preflight::{check_player_jersey, check_player_uri, retain_receipt},
+    prover::{ProofRequest, ProverConfig},
+};
+use clap::{Parser, ValueEnum};
+use common::allowlist::is_allowed_contract;
+use common::blocks::{check_block_range, max_spread};
+use common::cid::{ComputeCid, Player, SerializationFormat};
+use common::roster::Roster;
+use common::team::{Formation, ProofBundle, RatingBand, Team, TEAM_SIZE};
 use methods_player::VERIFY_CID_ELF;
-use methods_team::MAKE_TEAM_ELF;
+use methods_team::{MAKE_TEAM_ELF, MAKE_TEAM_ID};
 use risc0_ethereum_contracts::encode_seal;
-use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts, VerifierContext};
+use risc0_zkvm::{sha::Digest, ProverOpts};
 use risc0_steel::{
     ethereum::{EthEvmEnv, ETH_SEPOLIA_CHAIN_SPEC},
     host::BlockNumberOrTag,
@@ -49,9 +67,8 @@ alloy::sol! {
         function ownerOf(uint256 tokenId) external view returns (address owner);
     }
 
-    struct VerifyJournal {
-        Commitment commitment;
-        address owner;
+    interface IPlayer {
+        function jerseyOf(uint256 tokenId) external view returns (uint8 jersey);
     }
 }
 
@@ -78,9 +95,83 @@ struct Args {
     #[clap(long, env)]
     beacon_api_url: Option<Url>,
 
-    /// Address of the ERC20 token contract
-    #[clap(long, default_value = "ca991c3210075409787fe2a625c22b27fbA098f6")]
-    player_contract: Address,
+    /// JSON array of `{ "player", "token_id", "contract" }` entries making up the team
+    ///
+    /// An entry may give `"car": "<path>"` instead of `"player"` to load the player from a CARv1
+    /// file, verified against its declared root CID.
+    #[clap(long)]
+    team_input: PathBuf,
+
+    /// Also verify the player's jersey number against `jerseyOf` on the player contract
+    #[clap(long)]
+    verify_jersey: bool,
+
+    /// Formation the team lines up in
+    #[clap(long, default_value = "4-4-2")]
+    formation: Formation,
+
+    /// Lowest `overall_rating` the league admits for any player
+    #[clap(long, default_value_t = RatingBand::OPEN.min)]
+    min_rating: u8,
+
+    /// Highest `overall_rating` the league admits for any player
+    #[clap(long, default_value_t = RatingBand::OPEN.max)]
+    max_rating: u8,
+
+    /// URL of a self-hosted Bonsai-compatible prover
+    ///
+    /// Takes precedence over the `BONSAI_API_URL` environment variable. When unset, the prover is
+    /// selected by `default_prover()`.
+    #[clap(long)]
+    prover_url: Option<Url>,
+
+    /// API key for the prover given by `--prover-url`
+    #[clap(long)]
+    prover_api_key: Option<String>,
+
+    /// Encoding the players' metadata was pinned with, which determines their CIDs
+    #[clap(long, default_value = "json")]
+    serialization: SerializationFormat,
+
+    /// Normalize the players' strings to Unicode NFC before checking and proving them
+    ///
+    /// Differently encoded but identical names then share a CID, which must match the token URI.
+    #[clap(long)]
+    normalize_nfc: bool,
+
+    /// Require the on-chain token URI to use exactly the CID version computed locally
+    ///
+    /// By default a CIDv0 and CIDv1 addressing the same content are accepted.
+    #[clap(long)]
+    strict: bool,
+
+    /// Save every player's receipt into this directory as soon as it is proven
+    ///
+    /// Retained receipts survive a failed team proof for debugging.
+    #[clap(long)]
+    retain_receipts: Option<PathBuf>,
+
+    /// Write the team's proof bundle as JSON for offline auditing
+    #[clap(long)]
+    bundle_out: Option<PathBuf>,
+
+    /// Write the team receipt, bincode serialized and base64 encoded, to this path
+    ///
+    /// Pass `-` to stream it to stdout for piping into other tools. Human-readable output then
+    /// goes to stderr so stdout carries only the receipt.
+    #[clap(long)]
+    output_receipt: Option<PathBuf>,
+
+    /// Format of the summary printed to stdout
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+/// Output formats supported by the publisher summary.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[tokio::main]
@@ -89,6 +180,13 @@ async fn main() -> Result<()> {
     // Parse CLI Arguments: The application starts by parsing command-line arguments provided by the user.
     let args = Args::parse();
 
+    let stream_receipt = args.output_receipt.as_deref() == Some(Path::new("-"));
+    let mut output = Output::stdio(stream_receipt);
+
+    let prover_config = ProverConfig::from_args(args.prover_url, args.prover_api_key)?;
+    let reachable = prover_config.clone();
+    task::spawn_blocking(move || reachable.check_reachable(Duration::from_secs(5))).await??;
+
     // Create an alloy provider for that private key and URL.
     let wallet = EthereumWallet::from(args.eth_wallet_private_key);
     let provider = ProviderBuilder::new()
@@ -96,131 +194,293 @@ async fn main() -> Result<()> {
         .wallet(wallet)
         .on_http(args.rpc_url);
 
-    // ABI encode input: Before sending the proof request to the Bonsai proving service,
-    // the input number is ABI-encoded to match the format expected by the guest code running in the zkVM.
-    // let input = args.input.abi_encode();
+    let json = std::fs::read_to_string(&args.team_input)
+        .with_context(|| format!("failed to read {}", args.team_input.display()))?;
+    let mut roster = Roster::from_json(&json).context("invalid team input")?;
+    if args.normalize_nfc {
+        roster.normalize_nfc();
+    }
+    // The team guest rejects repeated players, so report every duplicate before proving
+    let duplicates = roster.find_cid_duplicates();
+    if !duplicates.is_empty() {
+        let pairs: Vec<String> = duplicates
+            .iter()
+            .map(|(first, second)| format!("{first} and {second}"))
+            .collect();
+        bail!("players share a CID: {}", pairs.join(", "));
+    }
+    for contract in &roster.contracts {
+        if !is_allowed_contract(contract) {
+            bail!("player contract {contract} is not allowlisted by the guest");
+        }
+    }
+
+    let verify_jersey = args.verify_jersey;
+    let strict = args.strict;
+    let serialization = args.serialization;
+    let formation = args.formation;
+    let band = RatingBand {
+        min: args.min_rating,
+        max: args.max_rating,
+    };
+    // The team guest rejects players outside the band, so fail before proving any of them.
+    // Eligibility does not depend on the owner, which is only known once the players are proven.
+    Team {
+        owner: Address::ZERO,
+        formation,
+        players: roster.players.clone(),
+    }
+    .check_rating_band(&band)
+    .context("team is ineligible for the league")?;
 
-    let player = gen_test_player();
+    let mut receipts = Vec::new();
+    let mut owners = Vec::with_capacity(roster.len());
+    let mut player_journals = Vec::with_capacity(roster.len());
+    let mut player_summaries = Vec::with_capacity(roster.len());
+    let mut commitments = Vec::with_capacity(roster.len());
+    // Execution blocks the players were proven at, used to pin the team to the latest one
+    let mut player_blocks = Vec::with_capacity(roster.len());
 
-    let token_id: U256 = U256::from(0);
+    for (index, player) in roster.players.iter().enumerate() {
+        let token_id = roster.token_ids[index];
+        let player_contract = roster.contracts[index];
 
+        // Each player is proven against the chain as of its own turn, so proofs of a large
+        // roster may land on different blocks
+        let block_number = provider.get_block_number().await?.saturating_sub(1);
+        player_blocks.push(block_number);
+
+        let mut env = EthEvmEnv::builder()
+            .provider(provider.clone())
+            .block_number_or_tag(BlockNumberOrTag::Number(block_number))
+            .build()
+            .await?;
+        env = env.with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
+
+        let mut contract = Contract::preflight(player_contract, &mut env);
+        let owner_call = IERC721::ownerOfCall {
+            tokenId: U256::from(token_id),
+        };
+        let uri_call = IERC721::tokenURICall {
+            tokenId: U256::from(token_id),
+        };
+        let owner_result = contract.call_builder(&owner_call).call().await?;
+        let uri_result = contract.call_builder(&uri_call).call().await?;
+        if verify_jersey {
+            let jersey_call = IPlayer::jerseyOfCall {
+                tokenId: U256::from(token_id),
+            };
+            let jersey_result = contract.call_builder(&jersey_call).call().await?;
+            log::info!("On-chain jersey: {}", jersey_result.jersey);
+            check_player_jersey(index, token_id, player, jersey_result.jersey)?;
+        }
+
+        // A mismatch would only surface as an opaque guest panic, so name the player here
+        let player_cid = player
+            .compute_cid_format(serialization)
+            .with_context(|| format!("player {index} cannot be encoded as {serialization}"))?
+            .uri();
+        check_player_uri(index, token_id, &player_cid, &uri_result.uri, strict)?;
+        if args.format == OutputFormat::Text {
+            writeln!(output.human(), "Player {index} (token {token_id})")?;
+            writeln!(output.human(), "Owner: {:?}", owner_result.owner)?;
+            writeln!(output.human(), "URI: {:?}", uri_result.uri)?;
+            writeln!(output.human(), "Player CID: {:?}", player_cid)?;
+        }
+
+        let evm_input = if let Some(beacon_api_url) = args.beacon_api_url.clone() {
+            #[allow(deprecated)]
+            env.into_beacon_input(beacon_api_url).await?
+        } else {
+            env.into_input().await?
+        };
+
+        let player = player.clone();
+        let prover_config = prover_config.clone();
+        let receipt = task::spawn_blocking(move || {
+            let mut request = ProofRequest::default();
+            request
+                .write(&evm_input)?
+                .write(&player)?
+                .write(&token_id)?
+                .write(&player_contract)?
+                .write(&verify_jersey)?
+                .write(&strict)?
+                .write(&serialization)?;
+
+            // Player receipts are only verified as assumptions of the team proof
+            prover_config.prove(request, VERIFY_CID_ELF, &ProverOpts::succinct())
+        })
+        .await?
+        .with_context(|| format!("failed to prove player {index} (token {token_id})"))?;
+        if let Some(dir) = &args.retain_receipts {
+            let path = retain_receipt(dir, index, token_id, &receipt)?;
+            log::info!("Retained player {index} receipt at {}", path.display());
+        }
+        let journal = &receipt.journal.bytes;
+
+        // Decode and log the commitment
+        let journal = VerifyJournal::abi_decode(journal, true).context("invalid journal")?;
+        let commitment = CommitmentReport::from(&journal.commitment);
+        log::debug!("Steel commitment: {:?}", journal.commitment);
+
+        player_summaries.push(serde_json::json!({
+            "index": index,
+            "token_id": token_id,
+            "owner": owner_result.owner,
+            "uri": uri_result.uri,
+            "player_cid": player_cid,
+            "journal_owner": journal.owner,
+            "journal_contract": journal.playerContract,
+            "jersey_verified": journal.jerseyVerified,
+            "jersey_number": journal.jerseyNumber,
+            "commitment": commitment,
+        }));
+        if args.format == OutputFormat::Text {
+            writeln!(output.human(), "Journal owner: {:?}", journal.owner)?;
+            writeln!(output.human(), "{}", commitment)?;
+        }
+
+        owners.push(journal.owner);
+        player_journals.push(Bytes::from(receipt.journal.bytes.clone()));
+        commitments.push(journal.commitment);
+        receipts.push(receipt);
+    }
+
+    let owner = owners[0];
+    if owners.iter().any(|other| *other != owner) {
+        bail!("all players of a team must share one owner");
+    }
+
+    // Block numbers, or timestamps for beacon commitments
+    let timepoints = distinct_timepoints(&commitments);
+    let version = commitment_point(&commitments[0]).version;
+    check_block_range(timepoints.iter().copied(), max_spread(version))
+        .context("player proofs are too far apart for one team")?;
+    log::info!("Players proven at {timepoints:?}");
+
+    // The team guest makes no calls, it only needs a commitment no older than any player's
+    let team_block = player_blocks.iter().copied().max().unwrap_or_default();
     let mut env = EthEvmEnv::builder()
         .provider(provider.clone())
-        .block_number_or_tag(BlockNumberOrTag::Parent)
+        .block_number_or_tag(BlockNumberOrTag::Number(team_block))
         .build()
         .await?;
     env = env.with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
-
-    let mut contract = Contract::preflight(args.player_contract, &mut env);
-    let owner_call = IERC721::ownerOfCall {
-        tokenId: U256::from(token_id),
-    };
-    let uri_call = IERC721::tokenURICall {
-        tokenId: U256::from(token_id),
-    };
-    let owner_result =  contract.call_builder(&owner_call).call().await?;
-    let uri_result = contract.call_builder(&uri_call).call().await?;
-
-    println!("Owner: {:?}", owner_result.owner);
-    println!("URI: {:?}", uri_result.uri);
-    println!("Player CID: {:?}", player.formatted_cid());
-
-    let evm_input = if let Some(beacon_api_url) = args.beacon_api_url {
+    let team_evm_input = if let Some(beacon_api_url) = args.beacon_api_url.clone() {
         #[allow(deprecated)]
         env.into_beacon_input(beacon_api_url).await?
     } else {
         env.into_input().await?
     };
-    let cloned_evm_input = evm_input.clone();
 
-    let prove_info = task::spawn_blocking(move || {
-        let env = ExecutorEnv::builder()
-            .write(&evm_input)?
-            .write(&player)?
-            .write(&token_id)?
-            .build()
-            .unwrap();
-
-        default_prover().prove_with_ctx(
-            env,
-            &VerifierContext::default(),
-            VERIFY_CID_ELF,
-            &ProverOpts::groth16(),
-        )
-    })
-    .await?
-    .context("failed to create CID verification proof")?;
-    let receipt = prove_info.receipt;
-    let journal = &receipt.journal.bytes;
-
-    // Decode and log the commitment
-    let journal = VerifyJournal::abi_decode(journal, true).context("invalid journal")?;
-    log::debug!("Steel commitment: {:?}", journal.commitment);
-
-    // ABI encode the seal.
-    let seal = encode_seal(&receipt).context("invalid receipt")?;
-
-    println!("Journal owner: {:?}", journal.owner);
-
-    let players: [Player; 11] = array_init(|_| gen_test_player());
-    let token_ids: [U256; 11] = [token_id; 11];
+    let players: [Player; TEAM_SIZE] = roster
+        .players
+        .clone()
+        .try_into()
+        .map_err(|_| anyhow!("a team needs exactly {TEAM_SIZE} players"))?;
+    let token_ids: [U256; TEAM_SIZE] = roster
+        .token_ids
+        .clone()
+        .try_into()
+        .map_err(|_| anyhow!("a team needs exactly {TEAM_SIZE} token ids"))?;
+    let contracts: [Address; TEAM_SIZE] = roster
+        .contracts
+        .clone()
+        .try_into()
+        .map_err(|_| anyhow!("a team needs exactly {TEAM_SIZE} player contracts"))?;
+    let commitments: [Commitment; TEAM_SIZE] = commitments
+        .try_into()
+        .map_err(|_| anyhow!("a team needs exactly {TEAM_SIZE} player commitments"))?;
 
-    let make_team_proof = task::spawn_blocking(move || {
-        let env = ExecutorEnv::builder()
-            .write(&cloned_evm_input)?
-            .write(&journal.owner)?
+    let receipt = task::spawn_blocking(move || {
+        let mut request = ProofRequest::default();
+        request
+            .write(&team_evm_input)?
+            .write(&owner)?
             .write(&players)?
             .write(&token_ids)?
-            .add_assumption(receipt)
-            .build()
-            .unwrap();
-
-        default_prover().prove_with_ctx(
-            env,
-            &VerifierContext::default(),
-            MAKE_TEAM_ELF,
-            &ProverOpts::groth16(),
-        )
+            .write(&contracts)?
+            .write(&commitments)?
+            .write(&verify_jersey)?
+            .write(&formation)?
+            .write(&band)?
+            .write(&serialization)?;
+        for receipt in receipts {
+            request.add_assumption(receipt);
+        }
+
+        // Verified on-chain by `buildTeam`
+        prover_config.prove(request, MAKE_TEAM_ELF, &ProverOpts::groth16())
     }).await?
     .context("failed to make team create proof")?;
 
-    let receipt = make_team_proof.receipt;
     let journal = &receipt.journal.bytes;
 
+    let journal = TeamJournal::abi_decode(journal, true).context("invalid team journal")?;
 
-    Ok(())
-}
+    // ABI encode the seal.
+    let seal = encode_seal(&receipt).context("invalid receipt")?;
+
+    if let Some(path) = &args.bundle_out {
+        let bundle = ProofBundle {
+            owner,
+            formation,
+            format: serialization,
+            players: roster.players.clone(),
+            token_ids: roster.token_ids.clone(),
+            contracts: roster.contracts.clone(),
+            player_journals,
+            image_id: B256::from_slice(Digest::from(MAKE_TEAM_ID).as_bytes()),
+            team_journal: Bytes::from(receipt.journal.bytes.clone()),
+            seal: Bytes::from(seal.clone()),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&bundle)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    match args.format {
+        OutputFormat::Text => {
+            writeln!(output.human(), "Team CID digest: {}", journal.teamCID)?;
+            writeln!(output.human(), "Registry hash: {}", journal.registryHash)?;
+            writeln!(
+                output.human(),
+                "Average rating: {:.2} (band {band})",
+                f64::from(journal.averageRating) / 100.0
+            )?;
+            let unit = if args.beacon_api_url.is_some() {
+                "Timestamps"
+            } else {
+                "Blocks"
+            };
+            writeln!(
+                output.human(),
+                "{unit}: {}..={}",
+                journal.minTimepoint,
+                journal.maxTimepoint
+            )?;
+        }
+        OutputFormat::Json => {
+            let summary = serde_json::json!({
+                "players": player_summaries,
+                "team_cid": journal.teamCID,
+                "registry_hash": journal.registryHash,
+                "rating_band": band,
+                "average_rating": journal.averageRating,
+                "min_timepoint": journal.minTimepoint,
+                "max_timepoint": journal.maxTimepoint,
+                "seal": Bytes::from(seal),
+            });
+            writeln!(output.human(), "{}", serde_json::to_string_pretty(&summary)?)?;
+        }
+    }
 
-fn gen_test_player() -> Player {
-    Player {
-        name: "Lionel Messi".to_string(),
-        jersey_number: 10,
-        description: "A professional footballer who plays as a forward for Paris Saint-Germain and the Argentina national team.".to_string(),
-        external_url: "https://en.wikipedia.org/wiki/Lionel_Messi".to_string(),
-        image: "https://upload.wikimedia.org/wikipedia/commons/4/47/Lionel_Messi_20180626.jpg".to_string(),
-        tier: 1,
-        overall_rating: 94.0,
-        skill_multiplier: 1.0,
-        skill: Skill {
-            speed: 90,
-            shooting: 95,
-            passing: 90,
-            dribbling: 96,
-            defense: 32,
-            physical: 68,
-            goal_tending: 0,
-        },
-        attributes: vec![
-            Attribute {
-                display_type: "Physical".to_string(),
-                trait_type: "Height".to_string(),
-                value: 170.0,
-            },
-            Attribute {
-                display_type: "Physical".to_string(),
-                trait_type: "Weight".to_string(),
-                value: 72.0,
-            },
-        ],
+    match &args.output_receipt {
+        Some(_) if stream_receipt => output.write_receipt(&receipt)?,
+        Some(path) => std::fs::write(path, encode_receipt(&receipt)? + "\n")
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => {}
     }
+
+    Ok(())
 }
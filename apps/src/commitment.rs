@@ -0,0 +1,141 @@
+use std::fmt;
+
+use alloy_primitives::{B256, U256};
+use common::blocks::{distinct_blocks, CommitmentPoint};
+use risc0_steel::Commitment;
+use serde::Serialize;
+
+/// The meaningful parts of a Steel [Commitment], decoded for display.
+///
+/// The commitment `id` packs a version in its top 16 bits: version 0 commits to an execution
+/// block hash and version 1 to a beacon block root (EIP-4788). The remaining bits hold the
+/// timepoint the proof was generated against: a block number for block commitments, a
+/// timestamp for beacon commitments.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CommitmentReport {
+    pub version: u16,
+    pub kind: &'static str,
+    pub timepoint: U256,
+    pub claim_id: U256,
+    pub digest: B256,
+    pub config_id: B256,
+}
+
+impl From<&Commitment> for CommitmentReport {
+    fn from(commitment: &Commitment) -> Self {
+        let (timepoint, version) = commitment.decode_id();
+        let kind = match version {
+            0 => "block",
+            1 => "beacon",
+            _ => "unknown",
+        };
+
+        CommitmentReport {
+            version,
+            kind,
+            timepoint,
+            claim_id: commitment.id,
+            digest: commitment.digest,
+            config_id: commitment.configID,
+        }
+    }
+}
+
+impl fmt::Display for CommitmentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Steel commitment:")?;
+        writeln!(f, "  version:   {} ({})", self.version, self.kind)?;
+        let label = match self.version {
+            1 => "timestamp:",
+            _ => "block:    ",
+        };
+        writeln!(f, "  {label} {}", self.timepoint)?;
+        writeln!(f, "  claim id:  {:#x}", self.claim_id)?;
+        writeln!(f, "  digest:    {}", self.digest)?;
+        write!(f, "  config id: {}", self.config_id)
+    }
+}
+
+/// Decodes a [Commitment] into the parts the team guest compares
+pub fn commitment_point(commitment: &Commitment) -> CommitmentPoint {
+    let (id, version) = commitment.decode_id();
+    CommitmentPoint {
+        id: id.to::<u64>(),
+        version,
+        config_id: commitment.configID,
+    }
+}
+
+/// Decoded ids of a set of commitments, block numbers or beacon timestamps, in ascending order
+/// without repeats
+pub fn distinct_timepoints(commitments: &[Commitment]) -> Vec<u64> {
+    distinct_blocks(
+        commitments
+            .iter()
+            .map(|commitment| commitment_point(commitment).id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use common::blocks::{team_commitment_range, BlockRange};
+
+    use super::*;
+
+    fn test_commitment() -> Commitment {
+        Commitment {
+            id: U256::from(6_800_123),
+            digest: B256::repeat_byte(0x11),
+            configID: B256::repeat_byte(0xab),
+        }
+    }
+
+    #[test]
+    fn test_render_commitment() {
+        let report = CommitmentReport::from(&test_commitment());
+        let rendered = report.to_string();
+
+        assert_eq!(report.version, 0);
+        assert_eq!(report.timepoint, U256::from(6_800_123));
+        assert!(rendered.contains("block:     6800123"));
+
+        let beacon = CommitmentReport::from(&Commitment {
+            id: (U256::from(1) << 240) | U256::from(1_727_000_000),
+            ..test_commitment()
+        });
+        assert_eq!(beacon.kind, "beacon");
+        assert_eq!(beacon.timepoint, U256::from(1_727_000_000));
+        assert!(beacon.to_string().contains("timestamp: 1727000000"));
+        assert!(rendered.contains(&format!("config id: {}", B256::repeat_byte(0xab))));
+    }
+
+    #[test]
+    fn test_commitment_json() {
+        let report = CommitmentReport::from(&test_commitment());
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["kind"], "block");
+        assert_eq!(json["config_id"], B256::repeat_byte(0xab).to_string());
+    }
+
+    #[test]
+    fn test_players_at_two_blocks() {
+        let early = test_commitment();
+        let late = Commitment {
+            id: U256::from(6_800_130),
+            ..test_commitment()
+        };
+        let commitments = [early.clone(), late.clone(), early];
+        assert_eq!(distinct_timepoints(&commitments), [6_800_123, 6_800_130]);
+
+        // The range the team guest commits for a team pinned to the latest player's block
+        let points: Vec<CommitmentPoint> = commitments.iter().map(commitment_point).collect();
+        assert_eq!(
+            team_commitment_range(&commitment_point(&late), &points),
+            Ok(BlockRange {
+                min: 6_800_123,
+                max: 6_800_130,
+            })
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{bail, Context, Result};
+use cid::Cid;
+use common::cid::compute_cid;
+use reqwest::blocking::{multipart, Client};
+use serde::Deserialize;
+use url::Url;
+
+/// Entry the node reports for each file passed to `/api/v0/add`
+#[derive(Deserialize)]
+struct AddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+/// Asks the IPFS node behind `node_api` which CID it would store `input` under and compares it
+/// with the CID computed locally.
+///
+/// The upload is a dry run: `only-hash` makes the node chunk and hash the data without writing
+/// or pinning it. The node is asked for a CIDv0 with its default chunker, matching the
+/// parameters [compute_cid] uses, so a mismatch points at a node configured differently.
+/// Returns the node's CID and whether it equals the local one.
+pub fn cid_matching_node(input: &[u8], node_api: &Url) -> Result<(Cid, bool)> {
+    let local = Cid::try_from(compute_cid(input).cid).context("invalid local CID")?;
+
+    let mut url = add_url(node_api)?;
+    url.query_pairs_mut()
+        .append_pair("only-hash", "true")
+        .append_pair("cid-version", "0")
+        .append_pair("pin", "false");
+
+    let form = multipart::Form::new().part("file", multipart::Part::bytes(input.to_vec()));
+    let response = Client::new()
+        .post(url)
+        .multipart(form)
+        .send()
+        .with_context(|| format!("IPFS node at {node_api} is unreachable"))?;
+    if !response.status().is_success() {
+        bail!("IPFS node returned {}", response.status());
+    }
+
+    let node = parse_add_response(&response.text()?)?;
+    Ok((node, node == local))
+}
+
+/// Resolves `api/v0/add` under `node_api`, keeping any path prefix such as a reverse proxy's
+fn add_url(node_api: &Url) -> Result<Url> {
+    // `join` replaces the last path segment unless the base ends in a slash
+    let mut base = node_api.clone();
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    base.join("api/v0/add")
+        .with_context(|| format!("invalid IPFS API URL: {node_api}"))
+}
+
+/// Parses the CID out of an `/api/v0/add` response body
+fn parse_add_response(body: &str) -> Result<Cid> {
+    // The endpoint streams one JSON object per line, the last one describing the added file
+    let line = body
+        .lines()
+        .rfind(|line| !line.trim().is_empty())
+        .context("empty response from IPFS node")?;
+    let response: AddResponse =
+        serde_json::from_str(line).context("unexpected response from IPFS node")?;
+    Cid::try_from(response.hash.as_str()).context("IPFS node returned an invalid CID")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSI_CID: &str = "Qmc3C1YoVD6HeXfDvtbqHq4eHCj7UxpAeKu6ZRKnFEeRBM";
+
+    #[test]
+    fn test_parse_add_response() {
+        let body =
+            format!("{{\"Name\":\"player.json\",\"Hash\":\"{MESSI_CID}\",\"Size\":\"615\"}}\n");
+        assert_eq!(
+            parse_add_response(&body).unwrap(),
+            Cid::try_from(MESSI_CID).unwrap()
+        );
+        assert!(parse_add_response("").is_err());
+    }
+
+    #[test]
+    fn test_add_url_keeps_path_prefix() {
+        for node_api in ["http://127.0.0.1:5001", "http://127.0.0.1:5001/"] {
+            assert_eq!(
+                add_url(&Url::parse(node_api).unwrap()).unwrap().as_str(),
+                "http://127.0.0.1:5001/api/v0/add"
+            );
+        }
+        for node_api in [
+            "https://gateway.example/ipfs",
+            "https://gateway.example/ipfs/",
+        ] {
+            assert_eq!(
+                add_url(&Url::parse(node_api).unwrap()).unwrap().as_str(),
+                "https://gateway.example/ipfs/api/v0/add"
+            );
+        }
+    }
+
+    /// Requires a local node, e.g. `ipfs daemon`, listening on `IPFS_API_URL`
+    #[cfg(feature = "ipfs-node-tests")]
+    #[test]
+    fn test_cid_matches_local_node() {
+        use common::fixtures;
+
+        let node_api =
+            std::env::var("IPFS_API_URL").unwrap_or_else(|_| "http://127.0.0.1:5001".to_string());
+        let node_api = Url::parse(&node_api).unwrap();
+
+        for fixture in fixtures::all() {
+            let input = serde_json::to_vec(&fixture.player).unwrap();
+            let (node, matches) = cid_matching_node(&input, &node_api).unwrap();
+
+            assert!(matches, "node reported {node}, expected {}", fixture.cid);
+            assert_eq!(node.to_string(), fixture.cid);
+        }
+    }
+}
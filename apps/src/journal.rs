@@ -0,0 +1,9 @@
+pub use common::journal::{TeamJournal, VerifyJournal};
+
+alloy::sol! {
+    /// Journal committed by the `aggregate_teams` guest
+    struct AggregateJournal {
+        uint256 teamCount;
+        bytes32 root;
+    }
+}
@@ -0,0 +1,29 @@
+//! Host-side helpers shared by the publisher and other off-chain tooling
+
+/// Aggregation of team receipts into a single tournament proof
+pub mod aggregate;
+
+/// Human-readable rendering of Steel commitments
+pub mod commitment;
+
+/// Comparison of locally computed CIDs with an IPFS node's
+pub mod ipfs;
+
+/// Journals committed by the guests
+pub mod journal;
+
+/// Stdout and stderr handling, including receipts piped through stdout
+pub mod output;
+
+/// Host-side checks of each player before it is proven
+pub mod preflight;
+
+/// Selection of the prover used for proof requests
+pub mod prover;
+
+/// Consumer-side verification of player receipts
+pub mod verify;
+
+/// Receipt helpers shared by the unit tests
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,114 @@
+use std::io::{self, Stderr, Stdout, Write};
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use risc0_zkvm::Receipt;
+
+/// Encodes a receipt as base64 over its bincode serialization
+pub fn encode_receipt(receipt: &Receipt) -> Result<String> {
+    let bytes = bincode::serialize(receipt).context("failed to serialize receipt")?;
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Decodes a receipt produced by [encode_receipt], ignoring surrounding whitespace
+pub fn decode_receipt(encoded: &str) -> Result<Receipt> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .context("receipt is not valid base64")?;
+    bincode::deserialize(&bytes).context("failed to deserialize receipt")
+}
+
+/// Output streams of a CLI that may pipe its receipt through stdout.
+///
+/// When the receipt is streamed, every human-readable line goes to stderr so stdout carries
+/// nothing but the encoded receipt.
+pub struct Output<O: Write, E: Write> {
+    stdout: O,
+    stderr: E,
+    stream_receipt: bool,
+}
+
+impl Output<Stdout, Stderr> {
+    /// Output over the process's stdout and stderr
+    pub fn stdio(stream_receipt: bool) -> Self {
+        Output::new(io::stdout(), io::stderr(), stream_receipt)
+    }
+}
+
+impl<O: Write, E: Write> Output<O, E> {
+    pub fn new(stdout: O, stderr: E, stream_receipt: bool) -> Self {
+        Output {
+            stdout,
+            stderr,
+            stream_receipt,
+        }
+    }
+
+    /// Writer for summaries and diagnostics meant for a person
+    pub fn human(&mut self) -> &mut dyn Write {
+        if self.stream_receipt {
+            &mut self.stderr
+        } else {
+            &mut self.stdout
+        }
+    }
+
+    /// Writes the encoded receipt as a single line on stdout
+    pub fn write_receipt(&mut self, receipt: &Receipt) -> Result<()> {
+        writeln!(self.stdout, "{}", encode_receipt(receipt)?)?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    /// Consumes the output, returning the underlying stdout and stderr
+    pub fn into_inner(self) -> (O, E) {
+        (self.stdout, self.stderr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_zkvm::sha::Digest;
+
+    use super::*;
+    use crate::testing::fake_receipt;
+
+    const IMAGE_ID: [u8; 32] = [0x11; 32];
+
+    #[test]
+    fn test_streamed_receipt_keeps_stdout_clean() {
+        let receipt = fake_receipt(IMAGE_ID, b"team journal".to_vec());
+        let mut output = Output::new(Vec::new(), Vec::new(), true);
+
+        writeln!(output.human(), "Team CID digest: 0x1234").unwrap();
+        output.write_receipt(&receipt).unwrap();
+        writeln!(output.human(), "Registry hash: 0x5678").unwrap();
+
+        let (stdout, stderr) = output.into_inner();
+        let stdout = String::from_utf8(stdout).unwrap();
+        let stderr = String::from_utf8(stderr).unwrap();
+
+        assert_eq!(stdout.lines().count(), 1);
+        let decoded = decode_receipt(&stdout).unwrap();
+        decoded.verify(Digest::from(IMAGE_ID)).unwrap();
+        assert_eq!(decoded.journal.bytes, receipt.journal.bytes);
+
+        assert!(stderr.contains("Team CID digest: 0x1234"));
+        assert!(stderr.contains("Registry hash: 0x5678"));
+    }
+
+    #[test]
+    fn test_summary_on_stdout_when_not_streaming() {
+        let mut output = Output::new(Vec::new(), Vec::new(), false);
+        writeln!(output.human(), "Team CID digest: 0x1234").unwrap();
+
+        let (stdout, stderr) = output.into_inner();
+        assert_eq!(stdout, b"Team CID digest: 0x1234\n");
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_base64() {
+        assert!(decode_receipt("not base64!").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::U256;
+use anyhow::{Context, Result};
+use common::cid::{uri_matches, Player};
+use risc0_zkvm::Receipt;
+
+/// A player whose `verify_cid` proof would fail because its CID disagrees with the chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerMismatch {
+    pub index: usize,
+    pub token_id: U256,
+    /// `ipfs://` URI computed from the player's metadata
+    pub computed: String,
+    /// Token URI read from the player contract
+    pub on_chain: String,
+}
+
+impl fmt::Display for PlayerMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "player {} (token {}): computed CID {} does not match on-chain URI {}",
+            self.index, self.token_id, self.computed, self.on_chain
+        )
+    }
+}
+
+impl std::error::Error for PlayerMismatch {}
+
+/// Checks the `ipfs://` URI computed for a player against its on-chain token URI before proving
+/// it.
+///
+/// Applies the same comparison as the `verify_cid` guest, so a player that passes here only
+/// fails to prove for reasons other than its metadata. Catching a mismatch on the host names
+/// the offending player, where the failed guest assertion would not.
+pub fn check_player_uri(
+    index: usize,
+    token_id: U256,
+    computed: &str,
+    on_chain: &str,
+    strict: bool,
+) -> Result<(), PlayerMismatch> {
+    if uri_matches(computed, on_chain, strict) {
+        return Ok(());
+    }
+
+    Err(PlayerMismatch {
+        index,
+        token_id,
+        computed: computed.to_string(),
+        on_chain: on_chain.to_string(),
+    })
+}
+
+/// A player whose jersey number in its metadata disagrees with `jerseyOf` on its contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JerseyMismatch {
+    pub index: usize,
+    pub token_id: U256,
+    pub metadata: u8,
+    pub on_chain: u8,
+}
+
+impl fmt::Display for JerseyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "player {} (token {}): metadata jersey {} does not match on-chain jersey {}",
+            self.index, self.token_id, self.metadata, self.on_chain
+        )
+    }
+}
+
+impl std::error::Error for JerseyMismatch {}
+
+/// Checks a player's jersey against its on-chain `jerseyOf` before proving it, mirroring the
+/// `verify_cid` guest's assertion when jersey verification is enabled
+pub fn check_player_jersey(
+    index: usize,
+    token_id: U256,
+    player: &Player,
+    on_chain: u8,
+) -> Result<(), JerseyMismatch> {
+    if player.matches_jersey(on_chain) {
+        return Ok(());
+    }
+
+    Err(JerseyMismatch {
+        index,
+        token_id,
+        metadata: player.jersey_number,
+        on_chain,
+    })
+}
+
+/// Writes a player's receipt, bincode serialized, into `dir` for inspecting failed team proofs
+pub fn retain_receipt(
+    dir: &Path,
+    index: usize,
+    token_id: U256,
+    receipt: &Receipt,
+) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let path = dir.join(format!("player-{index}-token-{token_id}.receipt"));
+    let bytes = bincode::serialize(receipt).context("failed to serialize receipt")?;
+    std::fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{cid::ComputeCid, fixtures};
+
+    use super::*;
+
+    #[test]
+    fn test_mismatch_names_player() {
+        let players = [
+            fixtures::forward(),
+            fixtures::keeper(),
+            fixtures::defender(),
+        ];
+        let on_chain = [
+            format!("ipfs://{}", fixtures::FORWARD_CID),
+            format!("ipfs://{}", fixtures::RESERVE_CID),
+            format!("ipfs://{}", fixtures::DEFENDER_CID),
+        ];
+
+        let errors: Vec<PlayerMismatch> = players
+            .iter()
+            .zip(&on_chain)
+            .enumerate()
+            .filter_map(|(index, (player, uri))| {
+                let computed = player.formatted_cid();
+                check_player_uri(index, U256::from(40 + index), &computed, uri, true).err()
+            })
+            .collect();
+
+        assert_eq!(
+            errors,
+            [PlayerMismatch {
+                index: 1,
+                token_id: U256::from(41),
+                computed: format!("ipfs://{}", fixtures::KEEPER_CID),
+                on_chain: format!("ipfs://{}", fixtures::RESERVE_CID),
+            }]
+        );
+        assert!(errors[0].to_string().starts_with("player 1 (token 41)"));
+    }
+
+    #[test]
+    fn test_jersey_mismatch_names_player() {
+        let player = fixtures::forward();
+        assert!(check_player_jersey(0, U256::ZERO, &player, 10).is_ok());
+
+        let err = check_player_jersey(4, U256::from(44), &player, 30).unwrap_err();
+        assert_eq!(
+            err,
+            JerseyMismatch {
+                index: 4,
+                token_id: U256::from(44),
+                metadata: 10,
+                on_chain: 30,
+            }
+        );
+        assert!(err.to_string().starts_with("player 4 (token 44)"));
+    }
+
+    #[test]
+    fn test_lenient_accepts_cid_v1() {
+        let v1 = cid::Cid::try_from(fixtures::FORWARD_CID)
+            .unwrap()
+            .into_v1()
+            .unwrap();
+        let uri = format!("ipfs://{v1}");
+        let computed = fixtures::forward().formatted_cid();
+
+        assert!(check_player_uri(0, U256::ZERO, &computed, &uri, false).is_ok());
+        assert!(check_player_uri(0, U256::ZERO, &computed, &uri, true).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use std::{net::TcpStream, thread, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use bonsai_sdk::blocking::Client;
+use risc0_zkvm::{
+    compute_image_id, default_prover, serde::to_vec, ExecutorEnv, ProverOpts, Receipt, ReceiptKind,
+    VerifierContext,
+};
+use serde::Serialize;
+use url::Url;
+
+/// Interval between status requests while a remote proof is running.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Selects which prover the publisher sends its proof requests to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProverConfig {
+    /// Defer to [default_prover], configured through `RISC0_PROVER` and `BONSAI_*` variables.
+    Default,
+    /// A Bonsai-compatible prover given explicitly, e.g. a self-hosted instance.
+    Remote { url: Url, api_key: String },
+}
+
+/// Guest input and assumptions of a single proving task, independent of the prover running it.
+///
+/// [ExecutorEnv] keeps its input private, so requests sent to a [ProverConfig::Remote] prover
+/// are assembled here instead and only turned into an environment for local proving.
+#[derive(Default)]
+pub struct ProofRequest {
+    input: Vec<u32>,
+    assumptions: Vec<Receipt>,
+}
+
+impl ProofRequest {
+    /// Appends `value` to the guest input, serialized as `env::read` expects it.
+    pub fn write<T: Serialize>(&mut self, value: &T) -> Result<&mut Self> {
+        self.input.extend(to_vec(value)?);
+        Ok(self)
+    }
+
+    /// Adds a receipt the guest verifies with `env::verify`.
+    pub fn add_assumption(&mut self, receipt: Receipt) -> &mut Self {
+        self.assumptions.push(receipt);
+        self
+    }
+
+    /// Builds the environment for running the request on this machine.
+    pub fn executor_env(self) -> Result<ExecutorEnv<'static>> {
+        let mut builder = ExecutorEnv::builder();
+        builder.write_slice(&self.input);
+        for receipt in self.assumptions {
+            builder.add_assumption(receipt);
+        }
+        builder.build()
+    }
+}
+
+impl ProverConfig {
+    /// Builds the configuration from the `--prover-url` and `--prover-api-key` flags.
+    pub fn from_args(url: Option<Url>, api_key: Option<String>) -> Result<Self> {
+        match (url, api_key) {
+            (None, None) => Ok(ProverConfig::Default),
+            (Some(url), Some(api_key)) => Ok(ProverConfig::Remote { url, api_key }),
+            (Some(_), None) => bail!("--prover-url requires --prover-api-key"),
+            (None, Some(_)) => bail!("--prover-api-key requires --prover-url"),
+        }
+    }
+
+    /// Fails early with a readable error if an explicitly configured prover cannot be reached.
+    ///
+    /// Blocks for up to `timeout` per resolved address, so async callers should run it on a
+    /// blocking task.
+    pub fn check_reachable(&self, timeout: Duration) -> Result<()> {
+        let ProverConfig::Remote { url, .. } = self else {
+            return Ok(());
+        };
+
+        let addrs = url
+            .socket_addrs(|| None)
+            .with_context(|| format!("invalid prover URL: {url}"))?;
+        if addrs
+            .iter()
+            .any(|addr| TcpStream::connect_timeout(addr, timeout).is_ok())
+        {
+            return Ok(());
+        }
+
+        bail!("prover at {url} is unreachable")
+    }
+
+    /// API URL and key the client connects with, or `None` when deferring to [default_prover].
+    ///
+    /// Taken from the configuration alone, so inherited `BONSAI_*` variables are neither read nor
+    /// overwritten.
+    pub fn endpoint(&self) -> Option<(String, &str)> {
+        let ProverConfig::Remote { url, api_key } = self else {
+            return None;
+        };
+        Some((url.as_str().trim_end_matches('/').to_string(), api_key))
+    }
+
+    /// Client for an explicitly configured prover, connecting to [ProverConfig::endpoint]
+    pub fn client(&self) -> Result<Option<Client>> {
+        let Some((url, api_key)) = self.endpoint() else {
+            return Ok(None);
+        };
+
+        let client = Client::from_parts(url, api_key.to_string(), risc0_zkvm::VERSION)
+            .context("failed to create prover client")?;
+        Ok(Some(client))
+    }
+
+    /// Proves `elf` over `request`, returning a receipt of the kind selected by `opts`.
+    ///
+    /// Receipts verified on-chain must be Groth16, while receipts a guest verifies as assumptions
+    /// must not be, so callers pick [ProverOpts::groth16] only for the final receipt and
+    /// [ProverOpts::succinct] otherwise. Remote provers return any kind other than Groth16 as a
+    /// succinct receipt. Blocks until the proof is done, so async callers should run it on a
+    /// blocking task.
+    pub fn prove(&self, request: ProofRequest, elf: &[u8], opts: &ProverOpts) -> Result<Receipt> {
+        match self.client()? {
+            None => Ok(default_prover()
+                .prove_with_ctx(
+                    request.executor_env()?,
+                    &VerifierContext::default(),
+                    elf,
+                    opts,
+                )?
+                .receipt),
+            Some(client) => prove_remote(&client, request, elf, opts),
+        }
+    }
+}
+
+/// Runs a proving session on a Bonsai-compatible prover, followed by its Groth16 conversion when
+/// `opts` asks for a Groth16 receipt.
+fn prove_remote(
+    client: &Client,
+    request: ProofRequest,
+    elf: &[u8],
+    opts: &ProverOpts,
+) -> Result<Receipt> {
+    let image_id = compute_image_id(elf)?.to_string();
+    client.upload_img(&image_id, elf.to_vec())?;
+    let input_id = client.upload_input(bytemuck::cast_slice(&request.input).to_vec())?;
+
+    let mut receipt_ids = Vec::new();
+    for receipt in &request.assumptions {
+        receipt_ids.push(client.upload_receipt(bincode::serialize(receipt)?)?);
+    }
+
+    let session = client.create_session(image_id, input_id, receipt_ids, false)?;
+    loop {
+        let status = session.status(client)?;
+        match status.status.as_str() {
+            "RUNNING" => thread::sleep(POLL_INTERVAL),
+            "SUCCEEDED" if matches!(opts.receipt_kind, ReceiptKind::Groth16) => break,
+            "SUCCEEDED" => {
+                let url = status
+                    .receipt_url
+                    .context("prover did not return a session receipt")?;
+                let receipt: Receipt = bincode::deserialize(&client.download(&url)?)?;
+                return Ok(receipt);
+            }
+            other => bail!(
+                "proving session {} {other}: {}",
+                session.uuid,
+                status.error_msg.unwrap_or_default()
+            ),
+        }
+    }
+
+    let snark = client.create_snark(session.uuid.clone())?;
+    loop {
+        let status = snark.status(client)?;
+        match status.status.as_str() {
+            "RUNNING" => thread::sleep(POLL_INTERVAL),
+            "SUCCEEDED" => {
+                let url = status
+                    .output
+                    .context("prover did not return a Groth16 receipt")?;
+                let receipt: Receipt = bincode::deserialize(&client.download(&url)?)?;
+                return Ok(receipt);
+            }
+            other => bail!(
+                "Groth16 conversion {} {other}: {}",
+                snark.uuid,
+                status.error_msg.unwrap_or_default()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_override_env() {
+        // Conflicting values a `default_prover` would otherwise pick up; no other test reads them
+        std::env::set_var("BONSAI_API_URL", "https://api.bonsai.xyz");
+        std::env::set_var("BONSAI_API_KEY", "env-key");
+
+        let url = Url::parse("http://prover.internal:8081").unwrap();
+        let config =
+            ProverConfig::from_args(Some(url.clone()), Some("flag-key".to_string())).unwrap();
+        let endpoint = config.endpoint();
+        let client = config.client();
+
+        std::env::remove_var("BONSAI_API_URL");
+        std::env::remove_var("BONSAI_API_KEY");
+
+        assert_eq!(
+            config,
+            ProverConfig::Remote {
+                url,
+                api_key: "flag-key".to_string()
+            }
+        );
+        assert_eq!(
+            endpoint,
+            Some(("http://prover.internal:8081".to_string(), "flag-key"))
+        );
+        assert!(client.unwrap().is_some());
+        assert!(ProverConfig::Default.endpoint().is_none());
+    }
+
+    #[test]
+    fn test_default_without_flags() {
+        assert_eq!(
+            ProverConfig::from_args(None, None).unwrap(),
+            ProverConfig::Default
+        );
+        assert!(ProverConfig::from_args(None, Some("key".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_unreachable_prover() {
+        let url = Url::parse("http://127.0.0.1:1").unwrap();
+        let config = ProverConfig::from_args(Some(url), Some("key".to_string())).unwrap();
+
+        let err = config
+            .check_reachable(Duration::from_millis(200))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "prover at http://127.0.0.1:1/ is unreachable"
+        );
+    }
+}
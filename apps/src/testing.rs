@@ -0,0 +1,12 @@
+use risc0_zkvm::{sha::Digest, FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+
+/// Builds a fake receipt for `journal` and enables `RISC0_DEV_MODE` so that it verifies.
+///
+/// Tests run in parallel in one process, so every test that verifies a fake receipt goes through
+/// here rather than setting the variable itself.
+pub(crate) fn fake_receipt(image_id: impl Into<Digest>, journal: Vec<u8>) -> Receipt {
+    std::env::set_var("RISC0_DEV_MODE", "1");
+
+    let claim = ReceiptClaim::ok(image_id.into(), journal.clone());
+    Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal)
+}
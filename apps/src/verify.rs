@@ -0,0 +1,277 @@
+use std::fmt;
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolValue;
+use common::cid::SerializationFormat;
+use risc0_steel::Commitment;
+use risc0_zkvm::{sha::Digest, Receipt, VerificationError};
+
+use crate::{commitment::CommitmentReport, journal::VerifyJournal};
+
+/// Journal fields a consumer expects a `verify_cid` receipt to commit to
+#[derive(Debug, Clone)]
+pub struct ExpectedJournal {
+    pub owner: Address,
+    pub contract: Address,
+    pub token_id: U256,
+    pub cid: String,
+    pub commitment: Commitment,
+    /// Encoding the player CID must have been computed with
+    pub format: SerializationFormat,
+}
+
+/// Reasons a `verify_cid` receipt is rejected by [verify_and_check]
+#[derive(Debug)]
+pub enum VerifyError {
+    Receipt(VerificationError),
+    Journal(alloy_sol_types::Error),
+    OwnerMismatch {
+        expected: Address,
+        actual: Address,
+    },
+    ContractMismatch {
+        expected: Address,
+        actual: Address,
+    },
+    TokenIdMismatch {
+        expected: U256,
+        actual: U256,
+    },
+    CidMismatch {
+        expected: String,
+        actual: String,
+    },
+    CommitmentMismatch {
+        expected: CommitmentReport,
+        actual: CommitmentReport,
+    },
+    FormatMismatch {
+        expected: SerializationFormat,
+        actual: u8,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Receipt(err) => write!(f, "receipt verification failed: {err}"),
+            VerifyError::Journal(err) => write!(f, "invalid journal: {err}"),
+            VerifyError::OwnerMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "journal owner {actual} does not match expected {expected}"
+                )
+            }
+            VerifyError::ContractMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "journal contract {actual} does not match expected {expected}"
+                )
+            }
+            VerifyError::TokenIdMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "journal token id {actual} does not match expected {expected}"
+                )
+            }
+            VerifyError::CidMismatch { expected, actual } => {
+                write!(f, "journal CID {actual} does not match expected {expected}")
+            }
+            VerifyError::CommitmentMismatch { expected, actual } => write!(
+                f,
+                "journal commitment does not match\nexpected {expected}\nactual {actual}"
+            ),
+            VerifyError::FormatMismatch { expected, actual } => write!(
+                f,
+                "journal format {actual} does not match expected {expected} ({})",
+                expected.id()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Verifies a `verify_cid` receipt against `image_id` and checks its journal commits to the
+/// expected owner, player contract, token id, player CID, serialization format and Steel commitment.
+pub fn verify_and_check(
+    receipt: &Receipt,
+    image_id: impl Into<Digest>,
+    expected: &ExpectedJournal,
+) -> Result<(), VerifyError> {
+    receipt.verify(image_id).map_err(VerifyError::Receipt)?;
+
+    let journal =
+        VerifyJournal::abi_decode(&receipt.journal.bytes, true).map_err(VerifyError::Journal)?;
+
+    if journal.owner != expected.owner {
+        return Err(VerifyError::OwnerMismatch {
+            expected: expected.owner,
+            actual: journal.owner,
+        });
+    }
+    if journal.playerContract != expected.contract {
+        return Err(VerifyError::ContractMismatch {
+            expected: expected.contract,
+            actual: journal.playerContract,
+        });
+    }
+    if journal.tokenId != expected.token_id {
+        return Err(VerifyError::TokenIdMismatch {
+            expected: expected.token_id,
+            actual: journal.tokenId,
+        });
+    }
+    // The same metadata has a different CID in each format, so a CID only matches in context
+    if journal.format != expected.format.id() {
+        return Err(VerifyError::FormatMismatch {
+            expected: expected.format,
+            actual: journal.format,
+        });
+    }
+    if journal.playerCID != expected.cid {
+        return Err(VerifyError::CidMismatch {
+            expected: expected.cid.clone(),
+            actual: journal.playerCID,
+        });
+    }
+
+    let expected_commitment = CommitmentReport::from(&expected.commitment);
+    let actual_commitment = CommitmentReport::from(&journal.commitment);
+    if actual_commitment != expected_commitment {
+        return Err(VerifyError::CommitmentMismatch {
+            expected: expected_commitment,
+            actual: actual_commitment,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const IMAGE_ID: [u8; 32] = [0x11; 32];
+    const CID: &str = "ipfs://Qmc3C1YoVD6HeXfDvtbqHq4eHCj7UxpAeKu6ZRKnFEeRBM";
+
+    fn expected() -> ExpectedJournal {
+        ExpectedJournal {
+            owner: Address::repeat_byte(0x44),
+            contract: Address::repeat_byte(0x66),
+            token_id: U256::from(7),
+            cid: CID.to_string(),
+            commitment: common::fixtures::commitment(),
+            format: SerializationFormat::Json,
+        }
+    }
+
+    fn fake_receipt(journal: VerifyJournal) -> Receipt {
+        testing::fake_receipt(IMAGE_ID, journal.abi_encode())
+    }
+
+    fn test_journal() -> VerifyJournal {
+        VerifyJournal {
+            commitment: common::fixtures::commitment(),
+            owner: Address::repeat_byte(0x44),
+            playerContract: Address::repeat_byte(0x66),
+            tokenId: U256::from(7),
+            playerCID: CID.to_string(),
+            jerseyVerified: false,
+            jerseyNumber: 0,
+            format: 0,
+        }
+    }
+
+    #[test]
+    fn test_valid_receipt() {
+        let receipt = fake_receipt(test_journal());
+        verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap();
+    }
+
+    #[test]
+    fn test_jersey_modes() {
+        let player = common::fixtures::forward();
+
+        for verified in [true, false] {
+            let journal = VerifyJournal {
+                jerseyVerified: verified,
+                jerseyNumber: player.committed_jersey(verified),
+                ..test_journal()
+            };
+            let receipt = fake_receipt(journal);
+            verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap();
+
+            let decoded = VerifyJournal::abi_decode(&receipt.journal.bytes, true).unwrap();
+            assert_eq!(decoded.jerseyVerified, verified);
+            if verified {
+                assert_eq!(decoded.jerseyNumber, 10);
+            } else {
+                // Unverified journals commit 0 rather than the unchecked metadata jersey
+                assert_eq!(decoded.jerseyNumber, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_image_id() {
+        let receipt = fake_receipt(test_journal());
+        let err = verify_and_check(&receipt, Digest::from([0x99; 32]), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::Receipt(_)));
+    }
+
+    #[test]
+    fn test_journal_field_mismatch() {
+        let mut journal = test_journal();
+        journal.owner = Address::repeat_byte(0x55);
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::OwnerMismatch { .. }));
+
+        let mut journal = test_journal();
+        journal.playerContract = Address::repeat_byte(0x77);
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::ContractMismatch { .. }));
+
+        let mut journal = test_journal();
+        journal.tokenId = U256::from(8);
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::TokenIdMismatch { .. }));
+
+        let mut journal = test_journal();
+        journal.playerCID = "ipfs://QmfUJETx1rbrqD1Tk7Rv84vYmtPEn6DSzeN9Sh7FfFJMHp".to_string();
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::CidMismatch { .. }));
+
+        let mut journal = test_journal();
+        journal.commitment.id = U256::from(6_800_124);
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(err, VerifyError::CommitmentMismatch { .. }));
+    }
+
+    #[test]
+    fn test_format_mismatch() {
+        let mut journal = test_journal();
+        journal.format = SerializationFormat::DagCbor.id();
+        let receipt = fake_receipt(journal);
+        let err = verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected()).unwrap_err();
+        assert!(matches!(
+            err,
+            VerifyError::FormatMismatch {
+                expected: SerializationFormat::Json,
+                actual: 3,
+            }
+        ));
+
+        let expected = ExpectedJournal {
+            format: SerializationFormat::DagCbor,
+            ..expected()
+        };
+        verify_and_check(&receipt, Digest::from(IMAGE_ID), &expected).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+use alloy_primitives::{address, Address};
+
+/// Player contracts the guests accept `ownerOf` and `tokenURI` results from.
+///
+/// The contract address is a runtime input to the guest, so without this list a prover could
+/// point it at any contract returning whatever owner and URI they like. Extending the list
+/// changes the guest image ID.
+pub const ALLOWED_PLAYER_CONTRACTS: &[Address] =
+    &[address!("ca991c3210075409787fe2a625c22b27fbA098f6")];
+
+/// Returns true if the contract is in [ALLOWED_PLAYER_CONTRACTS]
+pub fn is_allowed_contract(contract: &Address) -> bool {
+    ALLOWED_PLAYER_CONTRACTS.contains(contract)
+}
+
+/// Aborts if the contract is not in [ALLOWED_PLAYER_CONTRACTS]
+pub fn assert_allowed_contract(contract: &Address) {
+    assert!(
+        is_allowed_contract(contract),
+        "Player contract {contract} is not allowlisted"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_contract() {
+        assert_allowed_contract(&address!("ca991c3210075409787fe2a625c22b27fbA098f6"));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowlisted")]
+    fn test_rejects_unknown_contract() {
+        assert_allowed_contract(&Address::repeat_byte(0x66));
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// Largest number of blocks between the earliest and latest commitment of a team's proofs
+///
+/// Players may be proven as their ownership is checked over time, but a team should still
+/// reflect a single, recent view of the chain.
+pub const MAX_BLOCK_SPREAD: u64 = 256;
+
+/// Largest number of seconds between the earliest and latest beacon commitment of a team's
+/// proofs, the time [MAX_BLOCK_SPREAD] slots take
+pub const MAX_BEACON_SPREAD: u64 = MAX_BLOCK_SPREAD * SECONDS_PER_SLOT;
+
+const SECONDS_PER_SLOT: u64 = 12;
+
+/// Steel commitment version committing to an execution block, identified by its number
+pub const BLOCK_COMMITMENT: u16 = 0;
+/// Steel commitment version committing to a beacon block root, identified by its timestamp
+pub const BEACON_COMMITMENT: u16 = 1;
+
+/// Largest allowed spread between the ids of commitments of `version`, in that version's unit
+pub fn max_spread(version: u16) -> u64 {
+    match version {
+        BEACON_COMMITMENT => MAX_BEACON_SPREAD,
+        _ => MAX_BLOCK_SPREAD,
+    }
+}
+
+/// Inclusive range of commitment ids a team's proofs were generated against: block numbers for
+/// block commitments, timestamps for beacon commitments
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl BlockRange {
+    /// Smallest range covering every block, or `None` if there are none
+    pub fn covering(blocks: impl IntoIterator<Item = u64>) -> Option<BlockRange> {
+        blocks.into_iter().fold(None, |range, block| {
+            Some(match range {
+                None => BlockRange {
+                    min: block,
+                    max: block,
+                },
+                Some(BlockRange { min, max }) => BlockRange {
+                    min: min.min(block),
+                    max: max.max(block),
+                },
+            })
+        })
+    }
+
+    /// Distance between the earliest and latest id
+    pub fn spread(&self) -> u64 {
+        self.max - self.min
+    }
+}
+
+impl fmt::Display for BlockRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+/// Reasons a set of commitment ids is rejected by [check_block_range]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockRangeError {
+    Empty,
+    Spread { range: BlockRange, max_spread: u64 },
+}
+
+impl fmt::Display for BlockRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockRangeError::Empty => write!(f, "no commitments"),
+            BlockRangeError::Spread { range, max_spread } => write!(
+                f,
+                "commitments {range} are {} apart, more than the allowed {max_spread}",
+                range.spread()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockRangeError {}
+
+/// The parts of a Steel commitment the team guest compares, decoded from its id.
+///
+/// Mirrors `risc0_steel::Commitment::decode_id` so the checks run without depending on Steel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitmentPoint {
+    /// Block number or beacon timestamp, depending on `version`
+    pub id: u64,
+    pub version: u16,
+    pub config_id: B256,
+}
+
+/// Reasons a player commitment is rejected by [team_commitment_range]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitmentError {
+    ConfigMismatch { index: usize },
+    VersionMismatch { index: usize },
+    AfterTeam { index: usize },
+    Range(BlockRangeError),
+}
+
+impl fmt::Display for CommitmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitmentError::ConfigMismatch { index } => {
+                write!(f, "player {index} was proven on another chain")
+            }
+            CommitmentError::VersionMismatch { index } => {
+                write!(
+                    f,
+                    "player {index} commitment version differs from the team's"
+                )
+            }
+            CommitmentError::AfterTeam { index } => {
+                write!(f, "player {index} was proven after the team")
+            }
+            CommitmentError::Range(err) => write!(f, "player proofs are too far apart: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CommitmentError {}
+
+/// Checks player commitments against the team's and returns the range the team journal commits.
+///
+/// Players may be proven before the team, but against the same chain and kind of commitment,
+/// and the range covering the team and every player may span at most [max_spread] of the
+/// team's commitment version.
+pub fn team_commitment_range(
+    team: &CommitmentPoint,
+    players: &[CommitmentPoint],
+) -> Result<BlockRange, CommitmentError> {
+    for (index, player) in players.iter().enumerate() {
+        if player.config_id != team.config_id {
+            return Err(CommitmentError::ConfigMismatch { index });
+        }
+        if player.version != team.version {
+            return Err(CommitmentError::VersionMismatch { index });
+        }
+        if player.id > team.id {
+            return Err(CommitmentError::AfterTeam { index });
+        }
+    }
+
+    let ids = std::iter::once(team.id).chain(players.iter().map(|player| player.id));
+    check_block_range(ids, max_spread(team.version)).map_err(CommitmentError::Range)
+}
+
+/// Returns the range covering `blocks`, checking it spans at most `max_spread` blocks
+pub fn check_block_range(
+    blocks: impl IntoIterator<Item = u64>,
+    max_spread: u64,
+) -> Result<BlockRange, BlockRangeError> {
+    let range = BlockRange::covering(blocks).ok_or(BlockRangeError::Empty)?;
+    if range.spread() > max_spread {
+        return Err(BlockRangeError::Spread { range, max_spread });
+    }
+    Ok(range)
+}
+
+/// Distinct blocks in ascending order
+pub fn distinct_blocks(blocks: impl IntoIterator<Item = u64>) -> Vec<u64> {
+    let mut blocks: Vec<u64> = blocks.into_iter().collect();
+    blocks.sort_unstable();
+    blocks.dedup();
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_blocks() {
+        let blocks = [6_800_120, 6_800_123, 6_800_120, 6_800_123];
+
+        assert_eq!(distinct_blocks(blocks), [6_800_120, 6_800_123]);
+        assert_eq!(
+            check_block_range(blocks, MAX_BLOCK_SPREAD),
+            Ok(BlockRange {
+                min: 6_800_120,
+                max: 6_800_123,
+            })
+        );
+    }
+
+    fn point(id: u64, version: u16) -> CommitmentPoint {
+        CommitmentPoint {
+            id,
+            version,
+            config_id: B256::repeat_byte(0xab),
+        }
+    }
+
+    #[test]
+    fn test_team_commitment_range() {
+        let team = point(6_800_130, BLOCK_COMMITMENT);
+        let players = [
+            point(6_800_120, 0),
+            point(6_800_123, 0),
+            point(6_800_130, 0),
+        ];
+        assert_eq!(
+            team_commitment_range(&team, &players),
+            Ok(BlockRange {
+                min: 6_800_120,
+                max: 6_800_130,
+            })
+        );
+
+        let late = [point(6_800_120, 0), point(6_800_131, 0)];
+        assert_eq!(
+            team_commitment_range(&team, &late),
+            Err(CommitmentError::AfterTeam { index: 1 })
+        );
+
+        let mut other_chain = players;
+        other_chain[2].config_id = B256::repeat_byte(0xcd);
+        assert_eq!(
+            team_commitment_range(&team, &other_chain),
+            Err(CommitmentError::ConfigMismatch { index: 2 })
+        );
+    }
+
+    #[test]
+    fn test_beacon_spread_in_seconds() {
+        // Beacon commitments are identified by timestamps, 12 seconds per slot
+        let team = point(1_727_000_000, BEACON_COMMITMENT);
+        let within = [point(1_727_000_000 - MAX_BEACON_SPREAD, BEACON_COMMITMENT)];
+        assert!(team_commitment_range(&team, &within).is_ok());
+
+        let beyond = [point(
+            1_727_000_000 - MAX_BEACON_SPREAD - 1,
+            BEACON_COMMITMENT,
+        )];
+        assert!(matches!(
+            team_commitment_range(&team, &beyond),
+            Err(CommitmentError::Range(BlockRangeError::Spread { .. }))
+        ));
+
+        let mixed = [point(6_800_120, BLOCK_COMMITMENT)];
+        assert_eq!(
+            team_commitment_range(&team, &mixed),
+            Err(CommitmentError::VersionMismatch { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_spread_too_wide() {
+        let blocks = [6_800_000, 6_800_000 + MAX_BLOCK_SPREAD + 1];
+        assert!(matches!(
+            check_block_range(blocks, MAX_BLOCK_SPREAD),
+            Err(BlockRangeError::Spread { .. })
+        ));
+        assert_eq!(
+            check_block_range([], MAX_BLOCK_SPREAD),
+            Err(BlockRangeError::Empty)
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use std::{collections::HashMap, fmt, io::Cursor};
+
+use cid::Cid;
+use ipfs_unixfs::file::{adder::FileAdder, visit::IdleFileVisit};
+use serde::Serialize;
+
+use crate::cid::{compute_cid, compute_cid_v1, content_equals, Player};
+
+/// CBOR tag for CIDs in DAG-CBOR
+const CID_TAG: u64 = 42;
+
+/// Multicodec of a raw block, the leaf layout `ipfs add --cid-version=1` defaults to
+const RAW_CODEC: u64 = 0x55;
+
+/// Errors raised while reading or verifying a CAR
+#[derive(Debug)]
+pub enum CarError {
+    Truncated,
+    InvalidHeader(&'static str),
+    UnsupportedVersion(u64),
+    RootCount(usize),
+    Cid(cid::Error),
+    MissingBlock(Cid),
+    UnsupportedLeaf(Cid),
+    UnixFs(String),
+    RootMismatch { declared: Cid, computed: Cid },
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CarError::Truncated => write!(f, "CAR is truncated"),
+            CarError::InvalidHeader(reason) => write!(f, "invalid CAR header: {reason}"),
+            CarError::UnsupportedVersion(version) => {
+                write!(f, "unsupported CAR version: {version}")
+            }
+            CarError::RootCount(count) => write!(f, "expected a single root, found {count}"),
+            CarError::Cid(err) => write!(f, "invalid CID in CAR: {err}"),
+            CarError::MissingBlock(cid) => write!(f, "CAR is missing block {cid}"),
+            CarError::UnsupportedLeaf(cid) => {
+                write!(f, "raw leaf {cid} under a dag-pb root is not supported")
+            }
+            CarError::UnixFs(err) => write!(f, "invalid UnixFS file: {err}"),
+            CarError::RootMismatch { declared, computed } => write!(
+                f,
+                "CAR declares root {declared} but its content hashes to {computed}"
+            ),
+            CarError::Json(err) => write!(f, "invalid player metadata: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CarError {}
+
+impl From<cid::Error> for CarError {
+    fn from(err: cid::Error) -> Self {
+        CarError::Cid(err)
+    }
+}
+
+/// A CARv1 archive: a DAG-CBOR header declaring the roots, followed by `(CID, block)` sections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarFile {
+    pub roots: Vec<Cid>,
+    pub blocks: Vec<(Cid, Vec<u8>)>,
+}
+
+impl CarFile {
+    /// Imports `input` as a UnixFS file, using the same chunking as [compute_cid]
+    pub fn import(input: &[u8]) -> CarFile {
+        let mut adder = FileAdder::default();
+        let mut blocks = Vec::new();
+
+        let mut remaining = input;
+        while !remaining.is_empty() {
+            let (produced, consumed) = adder.push(remaining);
+            blocks.extend(produced);
+            remaining = &remaining[consumed..];
+        }
+        blocks.extend(adder.finish());
+
+        // ipfs-unixfs uses an older `cid` release, so convert through bytes
+        let blocks: Vec<(Cid, Vec<u8>)> = blocks
+            .into_iter()
+            .map(|(cid, block)| (Cid::try_from(cid.to_bytes()).unwrap(), block))
+            .collect();
+        let root = blocks.last().map(|(cid, _)| *cid).unwrap();
+
+        CarFile {
+            roots: vec![root],
+            blocks,
+        }
+    }
+
+    /// Imports the JSON serialization of `value`
+    pub fn from_serializable<T: Serialize>(value: &T) -> CarFile {
+        CarFile::import(serde_json::to_string(value).unwrap().as_bytes())
+    }
+
+    /// Parses a CARv1 archive
+    pub fn from_bytes(bytes: &[u8]) -> Result<CarFile, CarError> {
+        let mut reader = bytes;
+
+        let header_len = read_varint(&mut reader)? as usize;
+        let header = take(&mut reader, header_len)?;
+        let roots = decode_header(header)?;
+
+        let mut blocks = Vec::new();
+        while !reader.is_empty() {
+            let section_len = read_varint(&mut reader)? as usize;
+            let section = take(&mut reader, section_len)?;
+
+            let mut cursor = Cursor::new(section);
+            let cid = Cid::read_bytes(&mut cursor)?;
+            let data = section[cursor.position() as usize..].to_vec();
+            blocks.push((cid, data));
+        }
+
+        Ok(CarFile { roots, blocks })
+    }
+
+    /// Serializes the archive as CARv1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = encode_header(&self.roots);
+
+        let mut out = Vec::new();
+        write_varint(&mut out, header.len() as u64);
+        out.extend_from_slice(&header);
+        for (cid, data) in &self.blocks {
+            let cid = cid.to_bytes();
+            write_varint(&mut out, (cid.len() + data.len()) as u64);
+            out.extend_from_slice(&cid);
+            out.extend_from_slice(data);
+        }
+
+        out
+    }
+
+    /// Reassembles the UnixFS file rooted at the archive's single root.
+    ///
+    /// The root is either a raw block holding the whole file, or a dag-pb node whose leaves are
+    /// dag-pb too. Files chunked into raw leaves under a dag-pb root are rejected.
+    pub fn file_content(&self) -> Result<Vec<u8>, CarError> {
+        let root = match self.roots.as_slice() {
+            [root] => *root,
+            roots => return Err(CarError::RootCount(roots.len())),
+        };
+
+        let blocks: HashMap<Cid, &[u8]> = self
+            .blocks
+            .iter()
+            .map(|(cid, data)| (*cid, data.as_slice()))
+            .collect();
+        if root.codec() == RAW_CODEC {
+            return Ok(lookup(&blocks, root)?.to_vec());
+        }
+
+        let mut content = Vec::new();
+        let (chunk, _, _, mut step) = IdleFileVisit::default()
+            .start(lookup(&blocks, root)?)
+            .map_err(|err| CarError::UnixFs(err.to_string()))?;
+        content.extend_from_slice(chunk);
+
+        let mut cache = None;
+        while let Some(visit) = step {
+            let (next, _) = visit.pending_links();
+            let next = Cid::try_from(next.to_bytes())?;
+            if next.codec() == RAW_CODEC {
+                return Err(CarError::UnsupportedLeaf(next));
+            }
+            let next = lookup(&blocks, next)?;
+            let (chunk, next_step) = visit
+                .continue_walk(next, &mut cache)
+                .map_err(|err| CarError::UnixFs(err.to_string()))?;
+            content.extend_from_slice(chunk);
+            step = next_step;
+        }
+
+        Ok(content)
+    }
+}
+
+/// Extracts the player JSON from a CAR and checks it hashes to the declared root.
+///
+/// Usable from both host and guest: the root is recomputed from the extracted content, so a CAR
+/// whose header names a different root than its blocks describe is rejected, as is any
+/// tampering with the blocks themselves. A raw root is recomputed as a CIDv1 over the content,
+/// any other root with [compute_cid]. Roots are compared with [content_equals], so a CIDv1
+/// dag-pb root matches the CIDv0 that [compute_cid] produces.
+pub fn verify_car_player(bytes: &[u8]) -> Result<Player, CarError> {
+    let car = CarFile::from_bytes(bytes)?;
+    let content = car.file_content()?;
+
+    let declared = car.roots[0];
+    let computed = if declared.codec() == RAW_CODEC {
+        Cid::try_from(compute_cid_v1(RAW_CODEC, &content).cid)?
+    } else {
+        Cid::try_from(compute_cid(&content).cid)?
+    };
+    if !content_equals(&declared, &computed) {
+        return Err(CarError::RootMismatch { declared, computed });
+    }
+
+    serde_json::from_slice(&content).map_err(CarError::Json)
+}
+
+fn lookup<'a>(blocks: &HashMap<Cid, &'a [u8]>, cid: Cid) -> Result<&'a [u8], CarError> {
+    blocks.get(&cid).copied().ok_or(CarError::MissingBlock(cid))
+}
+
+fn take<'a>(reader: &mut &'a [u8], len: usize) -> Result<&'a [u8], CarError> {
+    if reader.len() < len {
+        return Err(CarError::Truncated);
+    }
+    let (head, tail) = reader.split_at(len);
+    *reader = tail;
+    Ok(head)
+}
+
+fn read_varint(reader: &mut &[u8]) -> Result<u64, CarError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(reader, 1)?[0];
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(CarError::InvalidHeader("varint overflow"))
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Reads a CBOR item head, returning its major type and argument
+fn read_cbor_head(reader: &mut &[u8]) -> Result<(u8, u64), CarError> {
+    let initial = take(reader, 1)?[0];
+    let major = initial >> 5;
+    let argument = match initial & 0x1f {
+        info @ 0..=23 => u64::from(info),
+        24 => u64::from(take(reader, 1)?[0]),
+        25 => u64::from(u16::from_be_bytes(take(reader, 2)?.try_into().unwrap())),
+        26 => u64::from(u32::from_be_bytes(take(reader, 4)?.try_into().unwrap())),
+        27 => u64::from_be_bytes(take(reader, 8)?.try_into().unwrap()),
+        _ => return Err(CarError::InvalidHeader("unsupported CBOR item")),
+    };
+    Ok((major, argument))
+}
+
+fn write_cbor_head(out: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+/// Decodes `{ "roots": [CID, ...], "version": 1 }`
+fn decode_header(mut header: &[u8]) -> Result<Vec<Cid>, CarError> {
+    let reader = &mut header;
+    let (major, entries) = read_cbor_head(reader)?;
+    if major != 5 {
+        return Err(CarError::InvalidHeader("header is not a map"));
+    }
+
+    let mut roots = None;
+    let mut version = None;
+    for _ in 0..entries {
+        let (major, len) = read_cbor_head(reader)?;
+        if major != 3 {
+            return Err(CarError::InvalidHeader("header key is not a string"));
+        }
+        match take(reader, len as usize)? {
+            b"version" => match read_cbor_head(reader)? {
+                (0, value) => version = Some(value),
+                _ => return Err(CarError::InvalidHeader("version is not an integer")),
+            },
+            b"roots" => {
+                let (major, count) = read_cbor_head(reader)?;
+                if major != 4 {
+                    return Err(CarError::InvalidHeader("roots is not an array"));
+                }
+                let mut cids = Vec::new();
+                for _ in 0..count {
+                    if read_cbor_head(reader)? != (6, CID_TAG) {
+                        return Err(CarError::InvalidHeader("root is not a CID"));
+                    }
+                    let (major, len) = read_cbor_head(reader)?;
+                    let bytes = take(reader, len as usize)?;
+                    // DAG-CBOR prefixes CID bytes with the identity multibase
+                    match bytes.split_first() {
+                        Some((&0, cid)) if major == 2 => cids.push(Cid::try_from(cid)?),
+                        _ => return Err(CarError::InvalidHeader("root is not a CID")),
+                    }
+                }
+                roots = Some(cids);
+            }
+            _ => return Err(CarError::InvalidHeader("unknown header field")),
+        }
+    }
+
+    match version {
+        Some(1) => {}
+        Some(version) => return Err(CarError::UnsupportedVersion(version)),
+        None => return Err(CarError::InvalidHeader("missing version")),
+    }
+    roots.ok_or(CarError::InvalidHeader("missing roots"))
+}
+
+/// Encodes the header with DAG-CBOR key ordering: "roots" sorts before "version"
+fn encode_header(roots: &[Cid]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_cbor_head(&mut out, 5, 2);
+
+    write_cbor_head(&mut out, 3, 5);
+    out.extend_from_slice(b"roots");
+    write_cbor_head(&mut out, 4, roots.len() as u64);
+    for root in roots {
+        let cid = root.to_bytes();
+        write_cbor_head(&mut out, 6, CID_TAG);
+        write_cbor_head(&mut out, 2, cid.len() as u64 + 1);
+        out.push(0);
+        out.extend_from_slice(&cid);
+    }
+
+    write_cbor_head(&mut out, 3, 7);
+    out.extend_from_slice(b"version");
+    write_cbor_head(&mut out, 0, 1);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cid::ComputeCid, fixtures};
+
+    #[test]
+    fn test_car_round_trip() {
+        let player = fixtures::forward();
+        let car = CarFile::from_serializable(&player);
+        let bytes = car.to_bytes();
+
+        assert_eq!(CarFile::from_bytes(&bytes).unwrap(), car);
+        assert_eq!(car.roots[0].to_bytes(), player.compute_cid().cid);
+
+        let verified = verify_car_player(&bytes).unwrap();
+        assert_eq!(verified.name, player.name);
+        assert_eq!(verified.cid_string(), player.cid_string());
+    }
+
+    #[test]
+    fn test_car_wrong_root() {
+        let mut car = CarFile::from_serializable(&fixtures::forward());
+        let other = fixtures::keeper().compute_cid().cid;
+        car.roots = vec![Cid::try_from(other).unwrap()];
+
+        // The declared root has no block in the archive
+        assert!(matches!(
+            verify_car_player(&car.to_bytes()),
+            Err(CarError::MissingBlock(_))
+        ));
+    }
+
+    #[test]
+    fn test_car_cid_v1_roots() {
+        let player = fixtures::forward();
+
+        // CIDv1 dag-pb, as `ipfs add --cid-version=1 --raw-leaves=false` produces
+        let mut car = CarFile::from_serializable(&player);
+        for cid in car
+            .roots
+            .iter_mut()
+            .chain(car.blocks.iter_mut().map(|(cid, _)| cid))
+        {
+            *cid = cid.into_v1().unwrap();
+        }
+        assert_eq!(
+            verify_car_player(&car.to_bytes()).unwrap().name,
+            player.name
+        );
+
+        // A single raw block, the `ipfs add --cid-version=1` default for small files
+        let json = serde_json::to_vec(&player).unwrap();
+        let root = Cid::try_from(compute_cid_v1(RAW_CODEC, &json).cid).unwrap();
+        let car = CarFile {
+            roots: vec![root],
+            blocks: vec![(root, json)],
+        };
+        assert_eq!(
+            verify_car_player(&car.to_bytes()).unwrap().name,
+            player.name
+        );
+
+        let forged = CarFile {
+            roots: vec![root],
+            blocks: vec![(root, serde_json::to_vec(&fixtures::keeper()).unwrap())],
+        };
+        assert!(matches!(
+            verify_car_player(&forged.to_bytes()),
+            Err(CarError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_car_tampered_block() {
+        let mut car = CarFile::from_serializable(&fixtures::forward());
+        let forged = CarFile::from_serializable(&fixtures::keeper());
+        // Keep the original root CID but swap in the content of another player
+        car.blocks[0].1 = forged.blocks[0].1.clone();
+
+        assert!(matches!(
+            verify_car_player(&car.to_bytes()),
+            Err(CarError::RootMismatch { .. })
+        ));
+    }
+}
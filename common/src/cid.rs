@@ -1,6 +1,10 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Serialize, Deserialize};
-use cid::Cid;
+use cid::{multihash::Multihash, Cid};
 use ipfs_unixfs::file::adder::FileAdder;
+use serde_json::{value::RawValue, Value};
+use sha2::{Digest, Sha256};
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -42,10 +46,110 @@ pub struct FileStats {
     pub bytes: u64,
 }
 
+/// Multicodec code for DAG-JSON
+pub const DAG_JSON_CODEC: u64 = 0x0129;
+
+/// Multicodec code for DAG-CBOR
+pub const DAG_CBOR_CODEC: u64 = 0x71;
+
+/// Multihash code for SHA2-256
+const SHA2_256: u64 = 0x12;
+
+impl FileStats {
+    /// `ipfs://` URI of the computed CID
+    pub fn uri(&self) -> String {
+        let cid = Cid::try_from(self.cid.as_slice()).unwrap();
+        ["ipfs://", &cid.to_string()].concat()
+    }
+}
+
+/// Encodings a value can be addressed under. Each yields a different CID for the same value,
+/// so a verifier must use the encoding the content was pinned with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerializationFormat {
+    /// Compact `serde_json` bytes in a UnixFS file, as used by [ComputeCid::compute_cid]
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// Pretty printed `serde_json` bytes in a UnixFS file
+    #[serde(rename = "pretty-json")]
+    PrettyJson,
+    /// Canonical DAG-JSON block, as used by [ComputeCid::compute_dag_json_cid]
+    #[serde(rename = "dag-json")]
+    DagJson,
+    /// Canonical DAG-CBOR block
+    #[serde(rename = "dag-cbor")]
+    DagCbor,
+}
+
+impl SerializationFormat {
+    /// Stable identifier committed as the `format` journal field
+    pub fn id(&self) -> u8 {
+        match self {
+            SerializationFormat::Json => 0,
+            SerializationFormat::PrettyJson => 1,
+            SerializationFormat::DagJson => 2,
+            SerializationFormat::DagCbor => 3,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SerializationFormat::Json => "json",
+            SerializationFormat::PrettyJson => "pretty-json",
+            SerializationFormat::DagJson => "dag-json",
+            SerializationFormat::DagCbor => "dag-cbor",
+        }
+    }
+}
+
+impl fmt::Display for SerializationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SerializationFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(SerializationFormat::Json),
+            "pretty-json" => Ok(SerializationFormat::PrettyJson),
+            "dag-json" => Ok(SerializationFormat::DagJson),
+            "dag-cbor" => Ok(SerializationFormat::DagCbor),
+            _ => Err(format!("unknown serialization format: {s}")),
+        }
+    }
+}
+
+/// Reasons a value cannot be encoded in a [SerializationFormat]
+#[derive(Debug)]
+pub enum SerializationError {
+    Json(serde_json::Error),
+    DagCbor(String),
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializationError::Json(err) => write!(f, "failed to encode JSON: {err}"),
+            SerializationError::DagCbor(err) => write!(f, "failed to encode DAG-CBOR: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
 pub trait ComputeCid: Serialize {
     fn compute_cid(&self) -> FileStats;
     fn cid_string(&self) -> String;
     fn formatted_cid(&self) -> String;
+    fn compute_dag_json_cid(&self) -> FileStats;
+    fn compute_cid_format(
+        &self,
+        format: SerializationFormat,
+    ) -> Result<FileStats, SerializationError>;
 }
 
 impl<T> ComputeCid for T
@@ -70,6 +174,73 @@ where
         let cid_string = self.cid_string();
         ["ipfs://", &cid_string].concat()
     }
+
+    /// CIDv1 of the value encoded as canonical DAG-JSON, for navigating metadata in IPLD
+    /// explorers.
+    ///
+    /// Unlike [ComputeCid::compute_cid], which hashes a UnixFS (dag-pb) file node wrapping the
+    /// JSON bytes and is what a tokenURI points at, this hashes the JSON itself as structured
+    /// IPLD data with the dag-json codec. The two CIDs never match and are not interchangeable.
+    fn compute_dag_json_cid(&self) -> FileStats {
+        let value = sort_keys(serde_json::to_value(self).unwrap());
+        let bytes = serde_json::to_vec(&value).unwrap();
+
+        compute_cid_v1(DAG_JSON_CODEC, &bytes)
+    }
+
+    /// Computes the CID of the value in the chosen encoding, the single entry point for picking
+    /// the representation an on-chain verifier expects.
+    ///
+    /// JSON and DAG-JSON delegate to [ComputeCid::compute_cid] and
+    /// [ComputeCid::compute_dag_json_cid], so each format has a single encoding.
+    fn compute_cid_format(
+        &self,
+        format: SerializationFormat,
+    ) -> Result<FileStats, SerializationError> {
+        match format {
+            SerializationFormat::Json => Ok(self.compute_cid()),
+            SerializationFormat::PrettyJson => {
+                let bytes = serde_json::to_vec_pretty(self).map_err(SerializationError::Json)?;
+                Ok(compute_cid(&bytes))
+            }
+            SerializationFormat::DagJson => Ok(self.compute_dag_json_cid()),
+            SerializationFormat::DagCbor => {
+                let bytes = serde_ipld_dagcbor::to_vec(self)
+                    .map_err(|err| SerializationError::DagCbor(err.to_string()))?;
+                Ok(compute_cid_v1(DAG_CBOR_CODEC, &bytes))
+            }
+        }
+    }
+}
+
+/// Sorts map keys bytewise, as DAG-JSON requires, regardless of serde_json's map ordering
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Computes a single-block SHA2-256 CIDv1 with the given codec
+pub(crate) fn compute_cid_v1(codec: u64, block: &[u8]) -> FileStats {
+    let digest = Sha256::digest(block);
+    let hash = Multihash::<64>::wrap(SHA2_256, &digest).unwrap();
+
+    FileStats {
+        cid: Cid::new_v1(codec, hash).to_bytes(),
+        blocks: 1,
+        bytes: block.len() as u64,
+    }
 }
 
 // Provided compute_cid function and FileAdder (assumed to be defined elsewhere)
@@ -95,13 +266,48 @@ pub fn compute_cid(input: &[u8]) -> FileStats {
     stats
 }
 
+/// Computes the CID over the exact bytes of already serialized JSON.
+///
+/// [ComputeCid::compute_cid] re-serializes its value, which may reorder keys, drop whitespace
+/// or reformat numbers relative to the JSON a caller received. A [RawValue] keeps the original
+/// text, so the CID matches the content the caller holds byte for byte.
+pub fn compute_cid_raw(raw: &RawValue) -> FileStats {
+    compute_cid(raw.get().as_bytes())
+}
+
+/// Returns true if both CIDs address the same content, regardless of CID version.
+///
+/// A CIDv0 and its CIDv1 counterpart share the codec (dag-pb) and multihash, differing only in
+/// version and string encoding.
+pub fn content_equals(a: &Cid, b: &Cid) -> bool {
+    a.codec() == b.codec() && a.hash() == b.hash()
+}
+
+/// Compares an expected `ipfs://` URI with the one stored on-chain.
+///
+/// Both are parsed into [Cid]s and compared structurally, so the multibase used to encode them
+/// (e.g. base32 versus base32upper) never causes a mismatch. In strict mode the CIDs must be
+/// identical, including their version, so the contract must store the same CID version the
+/// crate computes. Otherwise they are compared with [content_equals].
+pub fn uri_matches(expected: &str, on_chain: &str, strict: bool) -> bool {
+    match (parse_cid_uri(expected), parse_cid_uri(on_chain)) {
+        (Some(expected), Some(on_chain)) if strict => expected == on_chain,
+        (Some(expected), Some(on_chain)) => content_equals(&expected, &on_chain),
+        _ => false,
+    }
+}
+
+fn parse_cid_uri(uri: &str) -> Option<Cid> {
+    Cid::try_from(uri.strip_prefix("ipfs://").unwrap_or(uri)).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures;
 
-    #[test]
-    fn test_compute_cid() {
-        let player = Player {
+    fn test_player() -> Player {
+        Player {
             name: "Lionel Messi".to_string(),
             jersey_number: 10,
             description: "A professional footballer who plays as a forward for Paris Saint-Germain and the Argentina national team.".to_string(),
@@ -131,7 +337,12 @@ mod tests {
                     value: 72.0,
                 },
             ],
-        };
+        }
+    }
+
+    #[test]
+    fn test_compute_cid() {
+        let player = test_player();
 
         let stats = player.compute_cid();
         println!("{:?}", stats);
@@ -140,4 +351,98 @@ mod tests {
         // assert_eq!(stats.blocks, 1);
         // assert_eq!(stats.bytes, 1024);
     }
+
+    #[test]
+    fn test_uri_matches_across_versions() {
+        let player = fixtures::forward();
+        let v0 = Cid::try_from(player.compute_cid().cid).unwrap();
+        let v1 = Cid::new_v1(v0.codec(), *v0.hash());
+
+        let computed = player.formatted_cid();
+        let on_chain = ["ipfs://", &v1.to_string()].concat();
+        assert_ne!(computed, on_chain);
+
+        assert!(uri_matches(&computed, &on_chain, false));
+        assert!(!uri_matches(&computed, &on_chain, true));
+        assert!(uri_matches(&computed, &computed, true));
+    }
+
+    #[test]
+    fn test_uri_mismatched_content() {
+        let player = fixtures::forward();
+        let mut other = fixtures::forward();
+        other.jersey_number = 30;
+
+        let expected = player.formatted_cid();
+        assert!(!uri_matches(&expected, &other.formatted_cid(), false));
+        assert!(!uri_matches(&expected, "ipfs://not-a-cid", false));
+    }
+
+    #[test]
+    fn test_compute_cid_raw_preserves_bytes() {
+        let json = r#"{ "name": "Lionel Messi",  "jersey_number": 10 }"#;
+        let raw: Box<RawValue> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(compute_cid_raw(&raw).cid, compute_cid(json.as_bytes()).cid);
+        let reserialized = serde_json::to_value(&raw).unwrap().compute_cid();
+        assert_ne!(compute_cid_raw(&raw).cid, reserialized.cid);
+    }
+
+    #[test]
+    fn test_dag_json_cid() {
+        let player = fixtures::forward();
+        let stats = player.compute_dag_json_cid();
+        let cid = Cid::try_from(stats.cid.clone()).unwrap();
+
+        assert_eq!(cid.version(), cid::Version::V1);
+        assert_eq!(cid.codec(), DAG_JSON_CODEC);
+        assert_eq!(stats.cid, player.compute_dag_json_cid().cid);
+        assert_ne!(stats.cid, player.compute_cid().cid);
+    }
+
+    #[test]
+    fn test_compute_cid_formats() {
+        let player = fixtures::forward();
+        let formats = [
+            SerializationFormat::Json,
+            SerializationFormat::PrettyJson,
+            SerializationFormat::DagJson,
+            SerializationFormat::DagCbor,
+        ];
+
+        let cids: Vec<Vec<u8>> = formats
+            .iter()
+            .map(|format| player.compute_cid_format(*format).unwrap().cid)
+            .collect();
+        for (i, format) in formats.iter().enumerate() {
+            assert_eq!(player.compute_cid_format(*format).unwrap().cid, cids[i]);
+            assert!(cids[i + 1..].iter().all(|other| *other != cids[i]));
+            assert_eq!(
+                format.to_string().parse::<SerializationFormat>(),
+                Ok(*format)
+            );
+        }
+
+        assert_eq!(cids[0], player.compute_cid().cid);
+        assert_eq!(cids[2], player.compute_dag_json_cid().cid);
+        let dag_cbor = Cid::try_from(cids[3].clone()).unwrap();
+        assert_eq!(dag_cbor.codec(), DAG_CBOR_CODEC);
+    }
+
+    #[test]
+    fn test_uri_matches_uppercase_base32() {
+        let player = fixtures::forward();
+        let v0 = Cid::try_from(player.compute_cid().cid).unwrap();
+        let v1 = Cid::new_v1(v0.codec(), *v0.hash());
+
+        let computed = ["ipfs://", &v1.to_string()].concat();
+        let upper = v1
+            .to_string_of_base(cid::multibase::Base::Base32Upper)
+            .unwrap();
+        let on_chain = ["ipfs://", &upper].concat();
+        assert_ne!(computed, on_chain);
+
+        assert!(uri_matches(&computed, &on_chain, true));
+        assert!(uri_matches(&player.formatted_cid(), &on_chain, false));
+    }
 }
@@ -0,0 +1,183 @@
+//! Shared players for tests, with the CIDs their metadata is expected to produce, and the Steel
+//! commitment their journals are built against.
+//!
+//! The CIDs double as regression values: any change to serialization or chunking that alters
+//! them will fail `test_fixture_cids`.
+
+use alloy_primitives::{B256, U256};
+use risc0_steel::Commitment;
+
+use crate::cid::{Attribute, Player, Skill};
+
+pub const FORWARD_CID: &str = "QmfUJETx1rbrqD1Tk7Rv84vYmtPEn6DSzeN9Sh7FfFJMHp";
+pub const KEEPER_CID: &str = "QmbgWb17bmS75HQ1vZQDGFbWZftrsAkU562hABEnT7jH51";
+pub const DEFENDER_CID: &str = "QmT5cy9ycqTmLWNNHCxivk6qBMsHoeQX2HFyt13fLmtqiR";
+pub const RESERVE_CID: &str = "QmRzqkPQZDL7w3fsRQ1VYAHikPm8MsQj7fSRy12ph81D75";
+
+/// A player paired with the CID of its metadata
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub player: Player,
+    pub cid: &'static str,
+}
+
+/// Every fixture, in a stable order
+pub fn all() -> Vec<Fixture> {
+    vec![
+        Fixture {
+            player: forward(),
+            cid: FORWARD_CID,
+        },
+        Fixture {
+            player: keeper(),
+            cid: KEEPER_CID,
+        },
+        Fixture {
+            player: defender(),
+            cid: DEFENDER_CID,
+        },
+        Fixture {
+            player: reserve(),
+            cid: RESERVE_CID,
+        },
+    ]
+}
+
+/// A top tier attacker
+pub fn forward() -> Player {
+    Player {
+        name: "Lionel Messi".to_string(),
+        jersey_number: 10,
+        description: "A professional footballer who plays as a forward for Paris Saint-Germain and the Argentina national team.".to_string(),
+        external_url: "https://en.wikipedia.org/wiki/Lionel_Messi".to_string(),
+        image: "https://upload.wikimedia.org/wikipedia/commons/4/47/Lionel_Messi_20180626.jpg".to_string(),
+        tier: 1,
+        overall_rating: 94.0,
+        skill_multiplier: 1.0,
+        skill: Skill {
+            speed: 90,
+            shooting: 95,
+            passing: 90,
+            dribbling: 96,
+            defense: 32,
+            physical: 68,
+            goal_tending: 0,
+        },
+        attributes: physique(170.0, 72.0),
+    }
+}
+
+/// A goalkeeper, the only fixture with a high `goal_tending`
+pub fn keeper() -> Player {
+    Player {
+        name: "Alisson Becker".to_string(),
+        jersey_number: 1,
+        description: "A professional footballer who plays as a goalkeeper for Liverpool and the Brazil national team.".to_string(),
+        external_url: "https://en.wikipedia.org/wiki/Alisson_Becker".to_string(),
+        image: "https://upload.wikimedia.org/wikipedia/commons/2/2b/Alisson_Becker_2018.jpg".to_string(),
+        tier: 1,
+        overall_rating: 89.0,
+        skill_multiplier: 1.0,
+        skill: Skill {
+            speed: 52,
+            shooting: 18,
+            passing: 62,
+            dribbling: 40,
+            defense: 45,
+            physical: 78,
+            goal_tending: 90,
+        },
+        attributes: physique(193.0, 91.0),
+    }
+}
+
+/// A centre-back
+pub fn defender() -> Player {
+    Player {
+        name: "Virgil van Dijk".to_string(),
+        jersey_number: 4,
+        description: "A professional footballer who plays as a centre-back for Liverpool and the Netherlands national team.".to_string(),
+        external_url: "https://en.wikipedia.org/wiki/Virgil_van_Dijk".to_string(),
+        image: "https://upload.wikimedia.org/wikipedia/commons/8/8b/Virgil_van_Dijk_2018.jpg".to_string(),
+        tier: 1,
+        overall_rating: 89.0,
+        skill_multiplier: 1.0,
+        skill: Skill {
+            speed: 78,
+            shooting: 60,
+            passing: 71,
+            dribbling: 72,
+            defense: 91,
+            physical: 86,
+            goal_tending: 0,
+        },
+        attributes: physique(193.0, 92.0),
+    }
+}
+
+/// A low tier squad player with a reduced skill multiplier
+pub fn reserve() -> Player {
+    Player {
+        name: "Sam Carter".to_string(),
+        jersey_number: 23,
+        description: "A squad player who usually comes off the bench.".to_string(),
+        external_url: "https://example.com/players/sam-carter".to_string(),
+        image: "https://example.com/players/sam-carter.png".to_string(),
+        tier: 4,
+        overall_rating: 61.5,
+        skill_multiplier: 0.8,
+        skill: Skill {
+            speed: 64,
+            shooting: 55,
+            passing: 58,
+            dribbling: 60,
+            defense: 52,
+            physical: 63,
+            goal_tending: 0,
+        },
+        attributes: physique(178.0, 74.0),
+    }
+}
+
+/// A block commitment to Sepolia block 6,800,123
+pub fn commitment() -> Commitment {
+    Commitment {
+        id: U256::from(6_800_123),
+        digest: B256::repeat_byte(0x22),
+        configID: B256::repeat_byte(0x33),
+    }
+}
+
+fn physique(height: f64, weight: f64) -> Vec<Attribute> {
+    vec![
+        Attribute {
+            display_type: "number".to_string(),
+            trait_type: "Height".to_string(),
+            value: height,
+        },
+        Attribute {
+            display_type: "number".to_string(),
+            trait_type: "Weight".to_string(),
+            value: weight,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cid::ComputeCid;
+
+    #[test]
+    fn test_fixture_cids() {
+        for fixture in all() {
+            fixture.player.validate().unwrap();
+            assert_eq!(
+                fixture.player.cid_string(),
+                fixture.cid,
+                "CID regression for {}",
+                fixture.player.name
+            );
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use alloy_sol_types::sol;
+use risc0_steel::Commitment;
+
+sol! {
+    /// Journal committed by the `verify_cid` guest
+    #[derive(Debug)]
+    struct VerifyJournal {
+        Commitment commitment;
+        address owner;
+        address playerContract;
+        uint256 tokenId;
+        string playerCID;
+        bool jerseyVerified;
+        uint8 jerseyNumber;
+        uint8 format;
+    }
+
+    /// Journal committed by the `make_team` guest.
+    ///
+    /// `minTimepoint` and `maxTimepoint` bound the decoded ids of the team and player
+    /// commitments: block numbers for block commitments, timestamps for beacon commitments.
+    #[derive(Debug)]
+    struct TeamJournal {
+        Commitment commitment;
+        bytes32 teamCID;
+        uint256[11] playerIds;
+        address[11] playerContracts;
+        Commitment[11] playerCommitments;
+        bytes32 registryHash;
+        uint8 minRating;
+        uint8 maxRating;
+        uint16 averageRating;
+        uint64 minTimepoint;
+        uint64 maxTimepoint;
+        uint8 format;
+    }
+}
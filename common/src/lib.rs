@@ -1,11 +1,33 @@
 //! Core implementation for creating CIDs from serde_json
 //! serializable data with examples
 
+/// Player contracts accepted by the guests
+pub mod allowlist;
+
+/// Block ranges spanned by a team's commitments
+pub mod blocks;
+
+/// Import and verification of metadata distributed as CARv1
+pub mod car;
+
 /// CID serialization and deserialization
 pub mod cid;
 
+/// Shared players for tests
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+
+/// Journals committed by the guests
+pub mod journal;
+
+/// Merkle roots over aggregated journals
+pub mod merkle;
+
 /// Struct and implementation for players
 pub mod players;
 
+/// Aligned players, token ids and contracts for a team
+pub mod roster;
+
 /// Struct and implementation for team
 pub mod team;
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use alloy_primitives::{keccak256, B256};
+
+/// Prefix of a hashed leaf, so a leaf can never be mistaken for a parent node
+const LEAF_PREFIX: u8 = 0x00;
+/// Prefix of a hashed parent node
+const NODE_PREFIX: u8 = 0x01;
+
+/// Computes the keccak256 Merkle root over a list of leaves.
+///
+/// Each leaf is hashed as `keccak256(0x00 ‖ leaf)` and each parent as
+/// `keccak256(0x01 ‖ left ‖ right)`, so no 64-byte leaf can stand in for a subtree. An odd node
+/// at the end of a level is promoted unchanged, and an empty list has a zero root.
+pub fn merkle_root<T: AsRef<[u8]>>(leaves: &[T]) -> B256 {
+    let mut level: Vec<B256> = leaves.iter().map(|leaf| hash_leaf(leaf.as_ref())).collect();
+    if level.is_empty() {
+        return B256::ZERO;
+    }
+
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_node(left, right),
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    level[0]
+}
+
+/// Index pair of the first leaf repeated in `leaves`, together with the earlier leaf it repeats.
+///
+/// A repeated leaf would let one proof be counted twice under the same root.
+pub fn find_duplicate<T: AsRef<[u8]>>(leaves: &[T]) -> Option<(usize, usize)> {
+    let mut first_seen: HashMap<&[u8], usize> = HashMap::new();
+    for (index, leaf) in leaves.iter().enumerate() {
+        if let Some(&first) = first_seen.get(leaf.as_ref()) {
+            return Some((first, index));
+        }
+        first_seen.insert(leaf.as_ref(), index);
+    }
+    None
+}
+
+fn hash_leaf(leaf: &[u8]) -> B256 {
+    keccak256([&[LEAF_PREFIX], leaf].concat())
+}
+
+fn hash_node(left: &B256, right: &B256) -> B256 {
+    keccak256([&[NODE_PREFIX], left.as_slice(), right.as_slice()].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_and_single() {
+        assert_eq!(merkle_root::<Vec<u8>>(&[]), B256::ZERO);
+        assert_eq!(merkle_root(&[b"team"]), keccak256(b"\x00team"));
+    }
+
+    #[test]
+    fn test_odd_leaf_promoted() {
+        let leaves = [b"a", b"b", b"c"];
+        let [a, b, c] = leaves.map(|leaf| hash_leaf(leaf));
+
+        assert_eq!(merkle_root(&leaves), hash_node(&hash_node(&a, &b), &c));
+    }
+
+    #[test]
+    fn test_order_sensitive() {
+        assert_ne!(merkle_root(&[b"a", b"b"]), merkle_root(&[b"b", b"a"]));
+    }
+
+    #[test]
+    fn test_leaf_cannot_forge_node() {
+        let (a, b) = (hash_leaf(b"a"), hash_leaf(b"b"));
+        let forged = [a.as_slice(), b.as_slice()].concat();
+
+        assert_ne!(merkle_root(&[forged]), merkle_root(&[b"a", b"b"]));
+    }
+
+    #[test]
+    fn test_find_duplicate() {
+        assert_eq!(find_duplicate(&[b"a", b"b", b"c"]), None);
+        assert_eq!(find_duplicate(&[b"a", b"b", b"a", b"b"]), Some((0, 2)));
+    }
+}
@@ -1 +1,300 @@
 // TODO: Move code from cid here
+use std::{fmt, ops::Range};
+
+use cid::Cid;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::cid::{compute_cid, Attribute, ComputeCid, FileStats, Player};
+
+/// Upper bound for ratings and skill values
+pub const MAX_RATING: u8 = 100;
+
+/// `display_type` values OpenSea renders. An empty `display_type` marks a plain string trait.
+///
+/// Metadata pinned with other values, such as `"Physical"`, must be migrated by rewriting each
+/// attribute's `display_type` (usually to `"number"`) and re-pinning it. The rewrite changes the
+/// serialized JSON and therefore the CID, so the token URI must be updated to the new CID too.
+pub const ALLOWED_DISPLAY_TYPES: [&str; 4] = ["number", "boost_percentage", "boost_number", "date"];
+
+/// Reasons a player's metadata is rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerError {
+    EmptyName,
+    RatingOutOfRange(f64),
+    SkillOutOfRange {
+        skill: &'static str,
+        value: u8,
+    },
+    UnsupportedDisplayType {
+        trait_type: String,
+        display_type: String,
+    },
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerError::EmptyName => write!(f, "player name is empty"),
+            PlayerError::RatingOutOfRange(rating) => {
+                write!(f, "overall rating {rating} is outside 0..={MAX_RATING}")
+            }
+            PlayerError::SkillOutOfRange { skill, value } => {
+                write!(f, "{skill} skill {value} exceeds {MAX_RATING}")
+            }
+            PlayerError::UnsupportedDisplayType {
+                trait_type,
+                display_type,
+            } => write!(
+                f,
+                "{trait_type} attribute has unsupported display_type {display_type:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+impl Player {
+    /// Checks the metadata is well formed before it is pinned or proven
+    pub fn validate(&self) -> Result<(), PlayerError> {
+        if self.name.trim().is_empty() {
+            return Err(PlayerError::EmptyName);
+        }
+        if !(0.0..=f64::from(MAX_RATING)).contains(&self.overall_rating) {
+            return Err(PlayerError::RatingOutOfRange(self.overall_rating));
+        }
+
+        let skill = &self.skill;
+        let skills = [
+            ("speed", skill.speed),
+            ("shooting", skill.shooting),
+            ("passing", skill.passing),
+            ("dribbling", skill.dribbling),
+            ("defense", skill.defense),
+            ("physical", skill.physical),
+            ("goal_tending", skill.goal_tending),
+        ];
+        for (skill, value) in skills {
+            if value > MAX_RATING {
+                return Err(PlayerError::SkillOutOfRange { skill, value });
+            }
+        }
+
+        for attribute in &self.attributes {
+            if !attribute.has_allowed_display_type() {
+                return Err(PlayerError::UnsupportedDisplayType {
+                    trait_type: attribute.trait_type.clone(),
+                    display_type: attribute.display_type.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy with every string field in Unicode Normalization Form C.
+    ///
+    /// Visually identical names can be encoded differently, e.g. "Júnior" as a precomposed `ú`
+    /// (NFC) or as `u` followed by a combining acute accent (NFD), and each encoding yields a
+    /// different CID. NFC is used as it is the form recommended for web content and the one most
+    /// input methods already produce, so normalizing rarely changes existing metadata.
+    pub fn normalized(&self) -> Player {
+        Player {
+            name: nfc(&self.name),
+            description: nfc(&self.description),
+            external_url: nfc(&self.external_url),
+            image: nfc(&self.image),
+            attributes: self
+                .attributes
+                .iter()
+                .map(|attribute| Attribute {
+                    display_type: nfc(&attribute.display_type),
+                    trait_type: nfc(&attribute.trait_type),
+                    value: attribute.value,
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Opt-in variant of [ComputeCid::compute_cid] over the [normalized](Player::normalized)
+    /// player, so differently encoded but identical names converge on one CID
+    pub fn compute_cid_normalized(&self) -> FileStats {
+        self.normalized().compute_cid()
+    }
+
+    /// Returns true if the on-chain jersey number agrees with the player's metadata.
+    ///
+    /// A disagreement means the off-chain JSON and the contract state have diverged, which
+    /// should be treated as tampering rather than a formatting difference.
+    pub fn matches_jersey(&self, on_chain_jersey: u8) -> bool {
+        self.jersey_number == on_chain_jersey
+    }
+
+    /// `jerseyNumber` a `verify_cid` journal commits for this player: the verified jersey, or 0
+    /// when the jersey was not checked against the chain
+    pub fn committed_jersey(&self, verified: bool) -> u8 {
+        if verified {
+            self.jersey_number
+        } else {
+            0
+        }
+    }
+}
+
+impl Attribute {
+    /// Whether the `display_type` is empty or one of [ALLOWED_DISPLAY_TYPES]
+    pub fn has_allowed_display_type(&self) -> bool {
+        self.display_type.is_empty() || ALLOWED_DISPLAY_TYPES.contains(&self.display_type.as_str())
+    }
+}
+
+fn nfc(value: &str) -> String {
+    value.nfc().collect()
+}
+
+/// How a change in serialization affects a player's CID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CidChangeReport {
+    pub old_cid: String,
+    pub new_cid: String,
+    pub changed: bool,
+    /// Bytes of the old serialization not shared with the new one
+    pub old_range: Range<usize>,
+    /// Bytes of the new serialization not shared with the old one
+    pub new_range: Range<usize>,
+    pub old_snippet: String,
+    pub new_snippet: String,
+}
+
+/// Developer diagnostic for schema migrations such as renaming a serialized key.
+///
+/// Serializes `old` with the current field names and compares it against
+/// `new_serialization`, reporting both CIDs and the region where the bytes diverge. The region
+/// is everything between the longest common prefix and the longest common suffix, so a single
+/// renamed key shows up as one small range in each serialization.
+pub fn explain_cid_change(old: &Player, new_serialization: &str) -> CidChangeReport {
+    let old_serialization = serde_json::to_string(old).unwrap();
+    let old = old_serialization.as_bytes();
+    let new = new_serialization.as_bytes();
+
+    let old_cid = Cid::try_from(compute_cid(old).cid).unwrap().to_string();
+    let new_cid = Cid::try_from(compute_cid(new).cid).unwrap().to_string();
+
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_range = prefix..old.len() - suffix;
+    let new_range = prefix..new.len() - suffix;
+
+    CidChangeReport {
+        changed: old_cid != new_cid,
+        old_cid,
+        new_cid,
+        old_snippet: String::from_utf8_lossy(&old[old_range.clone()]).into_owned(),
+        new_snippet: String::from_utf8_lossy(&new[new_range.clone()]).into_owned(),
+        old_range,
+        new_range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain_cid_change, PlayerError};
+    use crate::{cid::ComputeCid, fixtures};
+
+    #[test]
+    fn test_matching_jersey() {
+        assert!(fixtures::forward().matches_jersey(10));
+    }
+
+    #[test]
+    fn test_mismatched_jersey() {
+        assert!(!fixtures::forward().matches_jersey(30));
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range() {
+        let mut player = fixtures::forward();
+        player.skill.shooting = 101;
+        assert_eq!(
+            player.validate(),
+            Err(PlayerError::SkillOutOfRange {
+                skill: "shooting",
+                value: 101
+            })
+        );
+
+        let mut player = fixtures::forward();
+        player.name = " ".to_string();
+        assert_eq!(player.validate(), Err(PlayerError::EmptyName));
+    }
+
+    #[test]
+    fn test_validate_display_types() {
+        let mut player = fixtures::forward();
+        player.attributes[0].display_type = "Physical".to_string();
+        assert_eq!(
+            player.validate(),
+            Err(PlayerError::UnsupportedDisplayType {
+                trait_type: "Height".to_string(),
+                display_type: "Physical".to_string(),
+            })
+        );
+
+        let mut player = fixtures::forward();
+        assert_eq!(player.attributes[0].display_type, "number");
+        player.validate().unwrap();
+
+        player.attributes[0].display_type = String::new();
+        player.validate().unwrap();
+    }
+
+    #[test]
+    fn test_normalized_names_share_cid() {
+        let mut composed = fixtures::forward();
+        composed.name = "Neymar J\u{fa}nior".to_string();
+        let mut decomposed = fixtures::forward();
+        decomposed.name = "Neymar Ju\u{301}nior".to_string();
+
+        assert_ne!(composed.compute_cid().cid, decomposed.compute_cid().cid);
+        assert_eq!(
+            composed.compute_cid_normalized().cid,
+            decomposed.compute_cid_normalized().cid
+        );
+        assert_eq!(
+            composed.compute_cid_normalized().cid,
+            composed.compute_cid().cid
+        );
+    }
+
+    #[test]
+    fn test_explain_renamed_key() {
+        let player = fixtures::forward();
+        let renamed = serde_json::to_string(&player)
+            .unwrap()
+            .replace("\"jersey_number\"", "\"jerseyNumber\"");
+
+        let report = explain_cid_change(&player, &renamed);
+        assert!(report.changed);
+        assert_ne!(report.old_cid, report.new_cid);
+        assert_eq!(report.old_snippet, "_n");
+        assert_eq!(report.new_snippet, "N");
+        assert_eq!(report.old_range.start, report.new_range.start);
+    }
+
+    #[test]
+    fn test_explain_unchanged() {
+        let player = fixtures::forward();
+        let serialized = serde_json::to_string(&player).unwrap();
+
+        let report = explain_cid_change(&player, &serialized);
+        assert!(!report.changed);
+        assert!(report.old_range.is_empty());
+        assert!(report.new_range.is_empty());
+    }
+}
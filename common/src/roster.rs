@@ -0,0 +1,341 @@
+use std::{collections::HashMap, fmt, path::PathBuf, str::FromStr};
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    car::{verify_car_player, CarError},
+    cid::{ComputeCid, Player},
+    players::PlayerError,
+    team::TEAM_SIZE,
+};
+
+/// One entry of a combined team input file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TeamInputEntry {
+    /// Player metadata given inline, exclusive with `car`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<Player>,
+    /// CARv1 file holding the player metadata, verified against its declared root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub car: Option<PathBuf>,
+    /// Token id as a decimal or `0x` prefixed hex string
+    pub token_id: String,
+    pub contract: Address,
+}
+
+/// Players aligned index by index with the token ids and contracts they are minted as
+#[derive(Debug, Clone)]
+pub struct Roster {
+    pub players: Vec<Player>,
+    pub token_ids: Vec<U256>,
+    pub contracts: Vec<Address>,
+}
+
+/// Reasons a team input is rejected
+#[derive(Debug)]
+pub enum RosterError {
+    Json(serde_json::Error),
+    Size {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidTokenId {
+        index: usize,
+        value: String,
+    },
+    InvalidPlayer {
+        index: usize,
+        error: PlayerError,
+    },
+    PlayerSource {
+        index: usize,
+    },
+    Io {
+        index: usize,
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Car {
+        index: usize,
+        error: CarError,
+    },
+    DuplicateTokenId {
+        first: usize,
+        second: usize,
+        token_id: U256,
+    },
+}
+
+impl fmt::Display for RosterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RosterError::Json(err) => write!(f, "invalid team input: {err}"),
+            RosterError::Size { expected, actual } => {
+                write!(f, "team input has {actual} players, expected {expected}")
+            }
+            RosterError::InvalidTokenId { index, value } => {
+                write!(f, "player {index} has an invalid token id: {value:?}")
+            }
+            RosterError::InvalidPlayer { index, error } => {
+                write!(f, "player {index} has invalid metadata: {error}")
+            }
+            RosterError::PlayerSource { index } => {
+                write!(
+                    f,
+                    "player {index} needs exactly one of \"player\" or \"car\""
+                )
+            }
+            RosterError::Io { index, path, error } => {
+                write!(
+                    f,
+                    "player {index}: failed to read {}: {error}",
+                    path.display()
+                )
+            }
+            RosterError::Car { index, error } => {
+                write!(f, "player {index} has an invalid CAR: {error}")
+            }
+            RosterError::DuplicateTokenId {
+                first,
+                second,
+                token_id,
+            } => write!(
+                f,
+                "players {first} and {second} share token id {token_id} on the same contract"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RosterError {}
+
+impl Roster {
+    /// Parses a combined team input: a JSON array of
+    /// `{ "player": {...}, "token_id": "123", "contract": "0x..." }` objects. An entry may name a
+    /// CARv1 file with `"car": "path/to/player.car"` instead of giving `"player"` inline; the
+    /// path is resolved against the working directory.
+    pub fn from_json(json: &str) -> Result<Roster, RosterError> {
+        let entries: Vec<TeamInputEntry> = serde_json::from_str(json).map_err(RosterError::Json)?;
+        Roster::from_entries(entries)
+    }
+
+    /// Splits entries into aligned arrays, checking there are exactly [TEAM_SIZE] of them, that
+    /// every player passes [Player::validate] and that no token is listed twice. Token ids only
+    /// need to be unique per contract.
+    pub fn from_entries(entries: Vec<TeamInputEntry>) -> Result<Roster, RosterError> {
+        if entries.len() != TEAM_SIZE {
+            return Err(RosterError::Size {
+                expected: TEAM_SIZE,
+                actual: entries.len(),
+            });
+        }
+
+        let mut roster = Roster {
+            players: Vec::with_capacity(TEAM_SIZE),
+            token_ids: Vec::with_capacity(TEAM_SIZE),
+            contracts: Vec::with_capacity(TEAM_SIZE),
+        };
+        let mut seen = HashMap::new();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let token_id =
+                U256::from_str(&entry.token_id).map_err(|_| RosterError::InvalidTokenId {
+                    index,
+                    value: entry.token_id.clone(),
+                })?;
+            if let Some(&first) = seen.get(&(entry.contract, token_id)) {
+                return Err(RosterError::DuplicateTokenId {
+                    first,
+                    second: index,
+                    token_id,
+                });
+            }
+            seen.insert((entry.contract, token_id), index);
+
+            let player = match (entry.player, entry.car) {
+                (Some(player), None) => player,
+                (None, Some(path)) => {
+                    let bytes = std::fs::read(&path).map_err(|error| RosterError::Io {
+                        index,
+                        path,
+                        error,
+                    })?;
+                    verify_car_player(&bytes).map_err(|error| RosterError::Car { index, error })?
+                }
+                _ => return Err(RosterError::PlayerSource { index }),
+            };
+            player
+                .validate()
+                .map_err(|error| RosterError::InvalidPlayer { index, error })?;
+
+            roster.players.push(player);
+            roster.token_ids.push(token_id);
+            roster.contracts.push(entry.contract);
+        }
+
+        Ok(roster)
+    }
+
+    /// Index pairs of players whose metadata has the same CID, pairing each repeat with the first
+    /// player it duplicates. Identical metadata is usually a data entry error, and the team guest
+    /// rejects it, so this reports every duplicate at once before anything is proven.
+    pub fn find_cid_duplicates(&self) -> Vec<(usize, usize)> {
+        let mut first_seen: HashMap<Vec<u8>, usize> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for (index, player) in self.players.iter().enumerate() {
+            let cid = player.compute_cid().cid;
+            match first_seen.get(&cid) {
+                Some(&first) => duplicates.push((first, index)),
+                None => {
+                    first_seen.insert(cid, index);
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Replaces every player with its [normalized](Player::normalized) form, so the roster is
+    /// checked and proven against NFC metadata. The token URIs must then point at metadata that
+    /// was pinned after normalizing.
+    pub fn normalize_nfc(&mut self) {
+        for player in &mut self.players {
+            *player = player.normalized();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{car::CarFile, fixtures};
+
+    const CONTRACT: &str = "0xca991c3210075409787fe2a625c22b27fbA098f6";
+
+    fn team_input(token_ids: &[&str]) -> String {
+        let entries: Vec<serde_json::Value> = token_ids
+            .iter()
+            .enumerate()
+            .map(|(i, token_id)| {
+                let mut player = fixtures::all()[i % 4].player.clone();
+                player.jersey_number = i as u8 + 1;
+                serde_json::json!({
+                    "player": player,
+                    "token_id": token_id,
+                    "contract": CONTRACT,
+                })
+            })
+            .collect();
+        serde_json::to_string(&entries).unwrap()
+    }
+
+    #[test]
+    fn test_parse_team_input() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "0x0b"];
+        let roster = Roster::from_json(&team_input(&ids)).unwrap();
+
+        assert_eq!(roster.len(), TEAM_SIZE);
+        assert_eq!(roster.token_ids[0], U256::from(1));
+        assert_eq!(roster.token_ids[10], U256::from(11));
+        assert_eq!(roster.players[1].name, fixtures::keeper().name);
+        assert_eq!(roster.players[1].jersey_number, 2);
+        assert!(roster
+            .contracts
+            .iter()
+            .all(|contract| *contract == CONTRACT.parse::<Address>().unwrap()));
+    }
+
+    #[test]
+    fn test_rejects_duplicate_token_ids() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "3", "9", "10", "11"];
+        let err = Roster::from_json(&team_input(&ids)).unwrap_err();
+
+        assert!(matches!(
+            err,
+            RosterError::DuplicateTokenId {
+                first: 2,
+                second: 7,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_player() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"];
+        let mut entries: Vec<TeamInputEntry> = serde_json::from_str(&team_input(&ids)).unwrap();
+        entries[4].player.as_mut().unwrap().attributes[0].display_type = "Physical".to_string();
+
+        assert!(matches!(
+            Roster::from_entries(entries).unwrap_err(),
+            RosterError::InvalidPlayer {
+                index: 4,
+                error: PlayerError::UnsupportedDisplayType { .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_player_from_car() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"];
+        let mut entries: Vec<TeamInputEntry> = serde_json::from_str(&team_input(&ids)).unwrap();
+
+        let player = entries[2].player.take().unwrap();
+        let path = std::env::temp_dir().join(format!("roster-{}.car", std::process::id()));
+        std::fs::write(&path, CarFile::from_serializable(&player).to_bytes()).unwrap();
+        entries[2].car = Some(path.clone());
+
+        let roster = Roster::from_entries(entries.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(roster.players[2].cid_string(), player.cid_string());
+
+        entries[2].player = Some(player);
+        assert!(matches!(
+            Roster::from_entries(entries).unwrap_err(),
+            RosterError::PlayerSource { index: 2 }
+        ));
+    }
+
+    #[test]
+    fn test_find_cid_duplicates() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"];
+        let mut roster = Roster::from_json(&team_input(&ids)).unwrap();
+        assert!(roster.find_cid_duplicates().is_empty());
+
+        roster.players[8] = roster.players[3].clone();
+        assert_eq!(roster.find_cid_duplicates(), [(3, 8)]);
+    }
+
+    #[test]
+    fn test_normalize_nfc() {
+        let ids = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"];
+        let mut roster = Roster::from_json(&team_input(&ids)).unwrap();
+        roster.players[3].name = "Neymar J\u{fa}nior".to_string();
+        roster.players[8] = roster.players[3].clone();
+        roster.players[8].name = "Neymar Ju\u{301}nior".to_string();
+        assert!(roster.find_cid_duplicates().is_empty());
+
+        roster.normalize_nfc();
+        assert_eq!(roster.players[8].name, "Neymar J\u{fa}nior");
+        assert_eq!(roster.find_cid_duplicates(), [(3, 8)]);
+    }
+
+    #[test]
+    fn test_rejects_wrong_size() {
+        let err = Roster::from_json(&team_input(&["1", "2"])).unwrap_err();
+        assert!(matches!(
+            err,
+            RosterError::Size {
+                expected: TEAM_SIZE,
+                actual: 2
+            }
+        ));
+    }
+}
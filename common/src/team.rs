@@ -1 +1,765 @@
-// TODO: Implement
+use std::{fmt, str::FromStr};
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use cid::Cid;
+use serde::{Deserialize, Serialize};
+
+use crate::cid::{ComputeCid, FileStats, Player, SerializationFormat};
+use crate::journal::{TeamJournal, VerifyJournal};
+use crate::players::MAX_RATING;
+
+/// Number of players making up a team
+pub const TEAM_SIZE: usize = 11;
+
+/// Tactical formation a team lines up in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formation {
+    #[serde(rename = "4-4-2")]
+    FourFourTwo,
+    #[serde(rename = "4-3-3")]
+    FourThreeThree,
+    #[serde(rename = "4-2-3-1")]
+    FourTwoThreeOne,
+    #[serde(rename = "3-5-2")]
+    ThreeFiveTwo,
+    #[serde(rename = "5-3-2")]
+    FiveThreeTwo,
+}
+
+impl Formation {
+    /// Stable identifier used when hashing the formation
+    pub fn id(&self) -> u8 {
+        match self {
+            Formation::FourFourTwo => 0,
+            Formation::FourThreeThree => 1,
+            Formation::FourTwoThreeOne => 2,
+            Formation::ThreeFiveTwo => 3,
+            Formation::FiveThreeTwo => 4,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Formation::FourFourTwo => "4-4-2",
+            Formation::FourThreeThree => "4-3-3",
+            Formation::FourTwoThreeOne => "4-2-3-1",
+            Formation::ThreeFiveTwo => "3-5-2",
+            Formation::FiveThreeTwo => "5-3-2",
+        }
+    }
+}
+
+impl fmt::Display for Formation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Formation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "4-4-2" => Ok(Formation::FourFourTwo),
+            "4-3-3" => Ok(Formation::FourThreeThree),
+            "4-2-3-1" => Ok(Formation::FourTwoThreeOne),
+            "3-5-2" => Ok(Formation::ThreeFiveTwo),
+            "5-3-2" => Ok(Formation::FiveThreeTwo),
+            _ => Err(format!("unknown formation: {s}")),
+        }
+    }
+}
+
+/// Inclusive `[min, max]` range of `overall_rating` a league admits for every player
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatingBand {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl RatingBand {
+    /// Band admitting every valid rating, for leagues without a cap or floor
+    pub const OPEN: RatingBand = RatingBand {
+        min: 0,
+        max: MAX_RATING,
+    };
+
+    /// Whether `rating` falls within the band
+    pub fn contains(&self, rating: f64) -> bool {
+        (f64::from(self.min)..=f64::from(self.max)).contains(&rating)
+    }
+}
+
+impl Default for RatingBand {
+    fn default() -> Self {
+        RatingBand::OPEN
+    }
+}
+
+impl fmt::Display for RatingBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.min, self.max)
+    }
+}
+
+/// Reasons a team is ineligible for a [RatingBand]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RatingError {
+    InvalidBand(RatingBand),
+    OutOfBand {
+        index: usize,
+        rating: f64,
+        band: RatingBand,
+    },
+}
+
+impl fmt::Display for RatingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingError::InvalidBand(band) => write!(f, "rating band {band} is empty"),
+            RatingError::OutOfBand {
+                index,
+                rating,
+                band,
+            } => write!(
+                f,
+                "player {index} overall rating {rating} is outside the band {band}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RatingError {}
+
+/// A team of players owned by a single address
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Team {
+    pub owner: Address,
+    pub formation: Formation,
+    pub players: Vec<Player>,
+}
+
+/// Metadata pinned for a team, referencing each player by its CID
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TeamMetadata {
+    pub owner: Address,
+    pub formation: Formation,
+    pub players: Vec<String>,
+}
+
+impl Team {
+    /// Raw CIDs of the team's players, sorted bytewise
+    pub fn sorted_player_cids(&self) -> Vec<Vec<u8>> {
+        let mut cids: Vec<Vec<u8>> = self
+            .players
+            .iter()
+            .map(|player| player.compute_cid().cid)
+            .collect();
+        cids.sort();
+        cids
+    }
+
+    /// Whether every player's metadata has a distinct CID
+    pub fn has_distinct_players(&self) -> bool {
+        self.sorted_player_cids()
+            .windows(2)
+            .all(|pair| pair[0] != pair[1])
+    }
+
+    /// Canonical team metadata. Players are listed in sorted CID order so any lineup ordering of
+    /// the same players yields the same metadata and therefore the same team CID.
+    pub fn metadata(&self) -> TeamMetadata {
+        let players = self
+            .sorted_player_cids()
+            .into_iter()
+            .map(|cid| ["ipfs://", &Cid::try_from(cid).unwrap().to_string()].concat())
+            .collect();
+
+        TeamMetadata {
+            owner: self.owner,
+            formation: self.formation,
+            players,
+        }
+    }
+
+    /// Computes the CID of the canonical team metadata
+    pub fn team_cid(&self) -> FileStats {
+        self.metadata().compute_cid()
+    }
+
+    /// Hash digest of the team CID, as committed in the `teamCID` journal field
+    pub fn team_cid_digest(&self) -> B256 {
+        let cid = Cid::try_from(self.team_cid().cid).unwrap();
+        B256::from_slice(cid.hash().digest())
+    }
+
+    /// Compact identifier for a league registry.
+    ///
+    /// Computed as `keccak256(teamCID || playerCIDs || owner || formation)` over the raw bytes of
+    /// the canonical team CID, the bytewise-sorted player CIDs, the 20 byte owner address and the
+    /// formation id. CIDs are self-delimiting, so the concatenation is unambiguous. Because both
+    /// the team CID and the player list are canonicalized, reordering the same set of players
+    /// preserves the hash, while changing any player, the owner or the formation changes it.
+    pub fn registry_hash(&self) -> [u8; 32] {
+        let mut preimage = self.team_cid().cid;
+        for cid in self.sorted_player_cids() {
+            preimage.extend_from_slice(&cid);
+        }
+        preimage.extend_from_slice(self.owner.as_slice());
+        preimage.push(self.formation.id());
+
+        keccak256(preimage).0
+    }
+
+    /// Checks every player's `overall_rating` falls within `band` and returns the team's average
+    /// rating in hundredths, as committed in the `averageRating` journal field.
+    pub fn check_rating_band(&self, band: &RatingBand) -> Result<u16, RatingError> {
+        if band.min > band.max {
+            return Err(RatingError::InvalidBand(*band));
+        }
+
+        for (index, player) in self.players.iter().enumerate() {
+            if !band.contains(player.overall_rating) {
+                return Err(RatingError::OutOfBand {
+                    index,
+                    rating: player.overall_rating,
+                    band: *band,
+                });
+            }
+        }
+
+        if self.players.is_empty() {
+            return Ok(0);
+        }
+        let total: f64 = self.players.iter().map(|p| p.overall_rating).sum();
+        Ok((total * 100.0 / self.players.len() as f64).round() as u16)
+    }
+}
+
+/// The public outputs of a team proof and its player proofs, as handed to an auditor
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProofBundle {
+    pub owner: Address,
+    pub formation: Formation,
+    /// Encoding the player CIDs were computed with
+    #[serde(default)]
+    pub format: SerializationFormat,
+    pub players: Vec<Player>,
+    /// Token id of each player, in lineup order
+    pub token_ids: Vec<U256>,
+    /// Contract each player was read from, in lineup order
+    pub contracts: Vec<Address>,
+    /// ABI encoded `verify_cid` journal of each player, exactly as committed by its receipt, in
+    /// lineup order
+    pub player_journals: Vec<Bytes>,
+    /// Image id of the `make_team` guest the team receipt was proven with. Not read by
+    /// [Team::audit].
+    pub image_id: B256,
+    /// ABI encoded team journal, exactly as committed by the receipt
+    pub team_journal: Bytes,
+    /// Seal of the team receipt, verifiable on-chain against `image_id` and `team_journal`. Not
+    /// read by [Team::audit].
+    pub seal: Bytes,
+}
+
+/// A journal value that does not match what its claimed inputs produce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discrepancy {
+    PlayerCid {
+        index: usize,
+        committed: String,
+        computed: String,
+    },
+    TeamCid {
+        committed: B256,
+        computed: B256,
+    },
+    RegistryHash {
+        committed: B256,
+        computed: B256,
+    },
+    PlayerId {
+        index: usize,
+        committed: U256,
+        claimed: U256,
+    },
+    PlayerContract {
+        index: usize,
+        committed: Address,
+        claimed: Address,
+    },
+    Format {
+        committed: u8,
+        claimed: SerializationFormat,
+    },
+}
+
+/// Values recomputed by a successful [Team::audit]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    pub players: usize,
+    pub team_cid: B256,
+    pub registry_hash: B256,
+}
+
+/// Reasons a [ProofBundle] fails its audit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditError {
+    PlayerCount {
+        players: usize,
+        journals: usize,
+    },
+    TokenCount {
+        players: usize,
+        token_ids: usize,
+        contracts: usize,
+    },
+    Journal(String),
+    PlayerJournal {
+        index: usize,
+        message: String,
+    },
+    Encoding {
+        index: usize,
+        message: String,
+    },
+    Inconsistent(Vec<Discrepancy>),
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditError::PlayerCount { players, journals } => {
+                write!(
+                    f,
+                    "bundle has {players} players but {journals} player journals"
+                )
+            }
+            AuditError::TokenCount {
+                players,
+                token_ids,
+                contracts,
+            } => write!(
+                f,
+                "bundle has {players} players but {token_ids} token ids and {contracts} contracts"
+            ),
+            AuditError::Journal(err) => write!(f, "invalid team journal: {err}"),
+            AuditError::PlayerJournal { index, message } => {
+                write!(f, "invalid journal for player {index}: {message}")
+            }
+            AuditError::Encoding { index, message } => {
+                write!(f, "player {index} cannot be encoded: {message}")
+            }
+            AuditError::Inconsistent(discrepancies) => {
+                write!(f, "{} journal values do not match", discrepancies.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+impl Team {
+    /// Checks offline that a bundle's journals genuinely derive from its players.
+    ///
+    /// The team and player journals are decoded from the bundle's raw bytes, and every player
+    /// CID, the team CID and the registry hash are recomputed locally and compared with the
+    /// committed values, as are the committed format, player ids and contracts with the claimed
+    /// bundle.
+    /// Every discrepancy is collected rather than stopping at the first. This catches a bundle
+    /// whose journals do not match its claimed players. `image_id` and `seal` are not used: the
+    /// receipts binding the journal bytes to the guests must be verified separately.
+    pub fn audit(bundle: &ProofBundle) -> Result<AuditReport, AuditError> {
+        if bundle.players.len() != bundle.player_journals.len() {
+            return Err(AuditError::PlayerCount {
+                players: bundle.players.len(),
+                journals: bundle.player_journals.len(),
+            });
+        }
+        if bundle.players.len() != bundle.token_ids.len()
+            || bundle.players.len() != bundle.contracts.len()
+        {
+            return Err(AuditError::TokenCount {
+                players: bundle.players.len(),
+                token_ids: bundle.token_ids.len(),
+                contracts: bundle.contracts.len(),
+            });
+        }
+        let journal = TeamJournal::abi_decode(&bundle.team_journal, true)
+            .map_err(|err| AuditError::Journal(err.to_string()))?;
+
+        let mut discrepancies = Vec::new();
+        if journal.format != bundle.format.id() {
+            discrepancies.push(Discrepancy::Format {
+                committed: journal.format,
+                claimed: bundle.format,
+            });
+        }
+        for (index, (player, player_journal)) in bundle
+            .players
+            .iter()
+            .zip(&bundle.player_journals)
+            .enumerate()
+        {
+            let committed = VerifyJournal::abi_decode(player_journal, true)
+                .map_err(|err| AuditError::PlayerJournal {
+                    index,
+                    message: err.to_string(),
+                })?
+                .playerCID;
+            let computed = player
+                .compute_cid_format(bundle.format)
+                .map_err(|err| AuditError::Encoding {
+                    index,
+                    message: err.to_string(),
+                })?
+                .uri();
+            if computed != committed {
+                discrepancies.push(Discrepancy::PlayerCid {
+                    index,
+                    committed,
+                    computed,
+                });
+            }
+        }
+
+        let team = Team {
+            owner: bundle.owner,
+            formation: bundle.formation,
+            players: bundle.players.clone(),
+        };
+        let team_cid = team.team_cid_digest();
+        if team_cid != journal.teamCID {
+            discrepancies.push(Discrepancy::TeamCid {
+                committed: journal.teamCID,
+                computed: team_cid,
+            });
+        }
+        let registry_hash = B256::from(team.registry_hash());
+        if registry_hash != journal.registryHash {
+            discrepancies.push(Discrepancy::RegistryHash {
+                committed: journal.registryHash,
+                computed: registry_hash,
+            });
+        }
+        for (index, (&committed, &claimed)) in
+            journal.playerIds.iter().zip(&bundle.token_ids).enumerate()
+        {
+            if committed != claimed {
+                discrepancies.push(Discrepancy::PlayerId {
+                    index,
+                    committed,
+                    claimed,
+                });
+            }
+        }
+        for (index, (&committed, &claimed)) in journal
+            .playerContracts
+            .iter()
+            .zip(&bundle.contracts)
+            .enumerate()
+        {
+            if committed != claimed {
+                discrepancies.push(Discrepancy::PlayerContract {
+                    index,
+                    committed,
+                    claimed,
+                });
+            }
+        }
+
+        if !discrepancies.is_empty() {
+            return Err(AuditError::Inconsistent(discrepancies));
+        }
+
+        Ok(AuditReport {
+            players: bundle.players.len(),
+            team_cid,
+            registry_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cid::Skill;
+
+    fn test_player(name: &str, jersey_number: u8) -> Player {
+        Player {
+            name: name.to_string(),
+            jersey_number,
+            description: "".to_string(),
+            external_url: "".to_string(),
+            image: "".to_string(),
+            tier: 1,
+            overall_rating: 80.0,
+            skill_multiplier: 1.0,
+            skill: Skill {
+                speed: 80,
+                shooting: 80,
+                passing: 80,
+                dribbling: 80,
+                defense: 80,
+                physical: 80,
+                goal_tending: 0,
+            },
+            attributes: vec![],
+        }
+    }
+
+    fn test_team() -> Team {
+        Team {
+            owner: Address::repeat_byte(0x42),
+            formation: Formation::FourFourTwo,
+            players: (1..=TEAM_SIZE as u8)
+                .map(|i| test_player(&format!("Player {i}"), i))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_registry_hash_preserved_by_reordering() {
+        let team = test_team();
+        let mut reordered = team.clone();
+        reordered.players.reverse();
+
+        assert_eq!(team.team_cid().cid, reordered.team_cid().cid);
+        assert_eq!(team.registry_hash(), reordered.registry_hash());
+    }
+
+    #[test]
+    fn test_registry_hash_changes_with_contents() {
+        let team = test_team();
+
+        let mut substituted = team.clone();
+        substituted.players[0] = test_player("Substitute", 12);
+        assert_ne!(team.registry_hash(), substituted.registry_hash());
+
+        let mut reformed = team.clone();
+        reformed.formation = Formation::FourThreeThree;
+        assert_ne!(team.registry_hash(), reformed.registry_hash());
+
+        let mut transferred = team.clone();
+        transferred.owner = Address::repeat_byte(0x24);
+        assert_ne!(team.registry_hash(), transferred.registry_hash());
+    }
+
+    #[test]
+    fn test_distinct_players() {
+        let mut team = test_team();
+        assert!(team.has_distinct_players());
+
+        team.players[10] = team.players[0].clone();
+        assert!(!team.has_distinct_players());
+    }
+
+    #[test]
+    fn test_formation_round_trip() {
+        let formation: Formation = "4-2-3-1".parse().unwrap();
+        assert_eq!(formation, Formation::FourTwoThreeOne);
+        assert_eq!(formation.to_string(), "4-2-3-1");
+        assert!("4-4-3".parse::<Formation>().is_err());
+    }
+
+    fn rated_team(ratings: [f64; TEAM_SIZE]) -> Team {
+        let mut team = test_team();
+        for (player, rating) in team.players.iter_mut().zip(ratings) {
+            player.overall_rating = rating;
+        }
+        team
+    }
+
+    #[test]
+    fn test_rating_band_within() {
+        let team = rated_team([
+            70.0, 72.0, 74.0, 76.0, 78.0, 80.0, 80.0, 82.0, 84.0, 86.0, 88.5,
+        ]);
+        let band = RatingBand { min: 70, max: 90 };
+        assert_eq!(team.check_rating_band(&band), Ok(7914));
+        assert_eq!(team.check_rating_band(&RatingBand::OPEN), Ok(7914));
+    }
+
+    #[test]
+    fn test_rating_band_capped_league() {
+        let mut ratings = [75.0; TEAM_SIZE];
+        ratings[9] = 91.0;
+        let team = rated_team(ratings);
+
+        let band = RatingBand { min: 0, max: 85 };
+        assert_eq!(
+            team.check_rating_band(&band),
+            Err(RatingError::OutOfBand {
+                index: 9,
+                rating: 91.0,
+                band,
+            })
+        );
+    }
+
+    #[test]
+    fn test_rating_band_floored_league() {
+        let team = rated_team([60.0; TEAM_SIZE]);
+
+        let band = RatingBand {
+            min: 65,
+            max: MAX_RATING,
+        };
+        assert!(matches!(
+            team.check_rating_band(&band),
+            Err(RatingError::OutOfBand { index: 0, .. })
+        ));
+        assert_eq!(
+            team.check_rating_band(&RatingBand { min: 90, max: 80 }),
+            Err(RatingError::InvalidBand(RatingBand { min: 90, max: 80 }))
+        );
+    }
+
+    /// `verify_cid` journals committing each player's CID in `format`
+    fn player_journals(players: &[Player], format: SerializationFormat) -> Vec<Bytes> {
+        players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                VerifyJournal {
+                    commitment: crate::fixtures::commitment(),
+                    owner: Address::repeat_byte(0x44),
+                    playerContract: Address::repeat_byte(0x66),
+                    tokenId: U256::from(index),
+                    playerCID: player.compute_cid_format(format).unwrap().uri(),
+                    jerseyVerified: false,
+                    jerseyNumber: 0,
+                    format: format.id(),
+                }
+                .abi_encode()
+                .into()
+            })
+            .collect()
+    }
+
+    fn test_bundle() -> ProofBundle {
+        let team = test_team();
+        let token_ids: Vec<U256> = (0..TEAM_SIZE).map(U256::from).collect();
+        let contracts = vec![Address::repeat_byte(0x66); TEAM_SIZE];
+        let commitment = crate::fixtures::commitment();
+        let journal = TeamJournal {
+            commitment: commitment.clone(),
+            teamCID: team.team_cid_digest(),
+            playerIds: token_ids.clone().try_into().unwrap(),
+            playerContracts: contracts.clone().try_into().unwrap(),
+            playerCommitments: std::array::from_fn(|_| commitment.clone()),
+            registryHash: team.registry_hash().into(),
+            minRating: 0,
+            maxRating: 100,
+            averageRating: 8000,
+            minTimepoint: 6_800_123,
+            maxTimepoint: 6_800_123,
+            format: SerializationFormat::Json.id(),
+        };
+        ProofBundle {
+            owner: team.owner,
+            formation: team.formation,
+            format: SerializationFormat::Json,
+            token_ids,
+            contracts,
+            player_journals: player_journals(&team.players, SerializationFormat::Json),
+            image_id: B256::repeat_byte(0x11),
+            team_journal: journal.abi_encode().into(),
+            seal: Bytes::new(),
+            players: team.players,
+        }
+    }
+
+    #[test]
+    fn test_audit_consistent_bundle() {
+        let bundle = test_bundle();
+        let report = Team::audit(&bundle).unwrap();
+        let journal = TeamJournal::abi_decode(&bundle.team_journal, true).unwrap();
+        assert_eq!(report.players, TEAM_SIZE);
+        assert_eq!(report.team_cid, journal.teamCID);
+    }
+
+    #[test]
+    fn test_audit_decodes_journal() {
+        let mut bundle = test_bundle();
+        bundle.token_ids[5] = U256::from(99);
+        let Err(AuditError::Inconsistent(discrepancies)) = Team::audit(&bundle) else {
+            panic!("bundle claiming another token passed the audit");
+        };
+        assert_eq!(
+            discrepancies,
+            [Discrepancy::PlayerId {
+                index: 5,
+                committed: U256::from(5),
+                claimed: U256::from(99),
+            }]
+        );
+
+        // Edited journal bytes no longer match the recomputed team
+        let mut journal = TeamJournal::abi_decode(&bundle.team_journal, true).unwrap();
+        journal.registryHash = B256::repeat_byte(0x99);
+        let mut bundle = test_bundle();
+        bundle.team_journal = journal.abi_encode().into();
+        let Err(AuditError::Inconsistent(discrepancies)) = Team::audit(&bundle) else {
+            panic!("edited journal passed the audit");
+        };
+        assert!(matches!(
+            discrepancies[..],
+            [Discrepancy::RegistryHash { .. }]
+        ));
+
+        bundle.team_journal = Bytes::from_static(b"not a journal");
+        assert!(matches!(Team::audit(&bundle), Err(AuditError::Journal(_))));
+
+        let mut bundle = test_bundle();
+        bundle.player_journals[2] = Bytes::from_static(b"not a journal");
+        assert!(matches!(
+            Team::audit(&bundle),
+            Err(AuditError::PlayerJournal { index: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_audit_uses_bundle_format() {
+        let mut bundle = test_bundle();
+        bundle.format = SerializationFormat::DagCbor;
+        bundle.player_journals = player_journals(&bundle.players, bundle.format);
+        let Err(AuditError::Inconsistent(discrepancies)) = Team::audit(&bundle) else {
+            panic!("bundle claiming another format passed the audit");
+        };
+        assert_eq!(
+            discrepancies,
+            [Discrepancy::Format {
+                committed: SerializationFormat::Json.id(),
+                claimed: SerializationFormat::DagCbor,
+            }]
+        );
+
+        let mut journal = TeamJournal::abi_decode(&bundle.team_journal, true).unwrap();
+        journal.format = bundle.format.id();
+        bundle.team_journal = journal.abi_encode().into();
+        Team::audit(&bundle).unwrap();
+    }
+
+    #[test]
+    fn test_audit_tampered_bundle() {
+        let mut bundle = test_bundle();
+        let forged = test_player("Forged", 99);
+        bundle.players[3] = forged.clone();
+
+        let Err(AuditError::Inconsistent(discrepancies)) = Team::audit(&bundle) else {
+            panic!("tampered bundle passed the audit");
+        };
+        assert_eq!(
+            discrepancies[0],
+            Discrepancy::PlayerCid {
+                index: 3,
+                committed: test_player("Player 4", 4).formatted_cid(),
+                computed: forged.formatted_cid(),
+            }
+        );
+        assert!(matches!(discrepancies[1], Discrepancy::TeamCid { .. }));
+        assert!(matches!(discrepancies[2], Discrepancy::RegistryHash { .. }));
+    }
+}
@@ -27,6 +27,15 @@ contract Team is ERC721Holder, ERC721EnumerableURI {
         Steel.Commitment commitment;
         bytes32 teamCID;
         uint256[11] playerIds;
+        address[11] playerContracts;
+        Steel.Commitment[11] playerCommitments;
+        bytes32 registryHash;
+        uint8 minRating;
+        uint8 maxRating;
+        uint16 averageRating;
+        uint64 minTimepoint;
+        uint64 maxTimepoint;
+        uint8 format;
     }
 
     //  ─────────────────────────────────────────────────────────────────────────────
@@ -36,6 +45,12 @@ contract Team is ERC721Holder, ERC721EnumerableURI {
     /// @notice Reverted if this contract is not approved to use given player
     error PlayerApprovalRequired(address owner);
 
+    /// @notice Reverted if a journal commits to a block that is not canonical on this chain
+    error InvalidCommitment();
+
+    /// @notice Reverted if a player was proven against another contract than `players`
+    error InvalidPlayerContract(address playerContract);
+
     //  ─────────────────────────────────────────────────────────────────────────────
     //  Fields
     //  ─────────────────────────────────────────────────────────────────────────────
@@ -63,16 +78,29 @@ contract Team is ERC721Holder, ERC721EnumerableURI {
     //  ─────────────────────────────────────────────────────────────────────────────
 
     /**
+     * @notice Builds a team from a `make_team` proof.
+     * @dev Players may be proven at earlier blocks than the team, so every player commitment is
+     *      validated alongside the team's to rule out proofs against a fork or fabricated state.
+     * @param journalData ABI encoded journal committed by the `make_team` guest
+     * @param seal Seal of the receipt over `journalData`
      */
-    function buildTeam(uint256[11] calldata playerIds, bytes32 teamURI, bytes calldata seal) public {
+    function buildTeam(bytes calldata journalData, bytes calldata seal) public {
+        Journal memory journal = abi.decode(journalData, (Journal));
+        if (!Steel.validateCommitment(journal.commitment)) revert InvalidCommitment();
+        for (uint256 i = 0; i < 11; i++) {
+            if (!Steel.validateCommitment(journal.playerCommitments[i])) revert InvalidCommitment();
+            if (journal.playerContracts[i] != address(players)) {
+                revert InvalidPlayerContract(journal.playerContracts[i]);
+            }
+        }
+
         _checkApproval(msg.sender);
 
         for (uint256 i = 0; i < 11; i++) {
-            _isAuthorized(playerIds[i], msg.sender);
+            _isAuthorized(journal.playerIds[i], msg.sender);
         }
 
-        bytes memory journal = abi.encode(playerIds, teamURI);
-        verifier.verify(seal, buildTeamImageId, sha256(journal));
+        verifier.verify(seal, buildTeamImageId, sha256(journalData));
     }
 
     //  ─────────────────────────────────────────────────────────────────────────────
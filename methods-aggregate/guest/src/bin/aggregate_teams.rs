@@ -0,0 +1,31 @@
+use alloy_primitives::U256;
+use alloy_sol_types::{sol, SolValue};
+use common::merkle::{find_duplicate, merkle_root};
+use methods_team::MAKE_TEAM_ID;
+use risc0_zkvm::guest::env;
+
+sol! {
+    struct Journal {
+        uint256 teamCount;
+        bytes32 root;
+    }
+}
+
+fn main() {
+    let journals: Vec<Vec<u8>> = env::read();
+    if let Some((first, second)) = find_duplicate(&journals) {
+        panic!("Team journals {first} and {second} are identical");
+    }
+
+    // Each team journal must be backed by a `make_team` receipt supplied as an assumption
+    for journal in &journals {
+        env::verify(MAKE_TEAM_ID, journal).unwrap();
+    }
+
+    let journal = Journal {
+        teamCount: U256::from(journals.len()),
+        root: merkle_root(&journals),
+    };
+
+    env::commit_slice(&journal.abi_encode());
+}
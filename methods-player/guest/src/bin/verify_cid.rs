@@ -1,10 +1,12 @@
 use std::io::Read;
 
-use common::cid::{Player, ComputeCid};
-use alloy_primitives::{U256, Address, address};
+use common::allowlist::assert_allowed_contract;
+use common::cid::{uri_matches, Player, ComputeCid, SerializationFormat};
+use common::journal::VerifyJournal;
+use alloy_primitives::{U256, Address};
 use alloy_sol_types::{sol, SolValue};
 use risc0_zkvm::guest::env;
-use risc0_steel::{ethereum::{EthEvmInput, ETH_SEPOLIA_CHAIN_SPEC}, Contract, Commitment};
+use risc0_steel::{ethereum::{EthEvmInput, ETH_SEPOLIA_CHAIN_SPEC}, Contract};
 
 // risc0_zkvm::guest::entry!(main);
 
@@ -14,24 +16,29 @@ sol! {
         function ownerOf(uint256 tokenId) external view returns (address owner);
     }
 
-    struct Journal {
-        Commitment commitment;
-        address owner;
+    interface IPlayer {
+        function jerseyOf(uint256 tokenId) external view returns (uint8 jersey);
     }
 }
 
-pub const PLAYER_CONTRACT_ADDRESS: Address = address!("ca991c3210075409787fe2a625c22b27fbA098f6");
-
 fn main() {
     let chain_config: EthEvmInput = env::read();
     let player: Player = env::read();
     let token_id: U256 = env::read();
+    let contract_address: Address = env::read();
+    let verify_jersey: bool = env::read();
+    let strict: bool = env::read();
+    let format: SerializationFormat = env::read();
 
     let env = chain_config
         .into_env()
         .with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
 
-    let contract = Contract::new(PLAYER_CONTRACT_ADDRESS, &env);
+    assert_allowed_contract(&contract_address);
+    player
+        .validate()
+        .unwrap_or_else(|err| panic!("Invalid player metadata: {err}"));
+    let contract = Contract::new(contract_address, &env);
 
     let owner_call = IERC721::ownerOfCall {
         tokenId: U256::from(token_id),
@@ -43,15 +50,39 @@ fn main() {
     };
     let player_cid = contract.call_builder(&player_cid_call).call().uri;
 
-    let expected_cid = player.formatted_cid();
+    let expected_cid = player
+        .compute_cid_format(format)
+        .unwrap_or_else(|err| panic!("Player cannot be encoded as {format}: {err}"))
+        .uri();
     assert!(
-        expected_cid == player_cid,
+        uri_matches(&expected_cid, &player_cid, strict),
         "Player CID does not match on-chain data"
     );
 
-    let journal = Journal {
+    // Only contracts which store the jersey on-chain expose `jerseyOf`
+    let jersey_number = if verify_jersey {
+        let jersey_call = IPlayer::jerseyOfCall {
+            tokenId: U256::from(token_id),
+        };
+        let jersey = contract.call_builder(&jersey_call).call().jersey;
+        assert!(
+            player.matches_jersey(jersey),
+            "Player jersey number does not match on-chain data"
+        );
+        jersey
+    } else {
+        0
+    };
+
+    let journal = VerifyJournal {
         commitment: env.into_commitment(),
         owner,
+        playerContract: contract_address,
+        tokenId: token_id,
+        playerCID: expected_cid,
+        jerseyVerified: verify_jersey,
+        jerseyNumber: jersey_number,
+        format: format.id(),
     };
 
     env::commit_slice(&journal.abi_encode());
@@ -1,7 +1,10 @@
 use std::io::Read;
 
 use methods_player::VERIFY_CID_ID;
-use common::cid::{Player, ComputeCid};
+use common::cid::{Player, ComputeCid, SerializationFormat};
+use common::blocks::{team_commitment_range, CommitmentPoint};
+use common::journal::{TeamJournal, VerifyJournal};
+use common::team::{Formation, RatingBand, Team};
 use alloy_primitives::{U256, Address, address};
 use alloy_sol_types::{sol, SolValue};
 use risc0_zkvm::guest::env;
@@ -14,17 +17,6 @@ sol! {
         function tokenURI(uint256 tokenId) external view returns (string memory uri);
         function ownerOf(uint256 tokenId) external view returns (address owner);
     }
-
-    struct VerifyJournal {
-        Commitment commitment;
-        address owner;
-    }
-
-    struct Journal {
-        Commitment commitment;
-        bytes32 teamCID;
-        uint256[11] playerIds;
-    }
 }
 
 pub const PLAYER_CONTRACT_ADDRESS: Address = address!("ca991c3210075409787fe2a625c22b27fbA098f6");
@@ -34,23 +26,82 @@ fn main() {
     let owner: Address = env::read();
     let players: [Player; 11] = env::read();
     let token_ids: [U256; 11] = env::read();
+    let contracts: [Address; 11] = env::read();
+    let commitments: [Commitment; 11] = env::read();
+    let verify_jersey: bool = env::read();
+    let formation: Formation = env::read();
+    let band: RatingBand = env::read();
+    let format: SerializationFormat = env::read();
 
     let env = chain_config
         .into_env()
         .with_chain_spec(&ETH_SEPOLIA_CHAIN_SPEC);
 
+    // Players may be proven before the team, as long as they are proven against the same chain
+    // and kind of commitment within the allowed spread. The player commitments are committed
+    // too, so the verifying contract checks each one is a canonical block of its chain.
+    let point = |commitment: &Commitment| {
+        let (id, version) = commitment.decode_id();
+        CommitmentPoint {
+            id: id.to::<u64>(),
+            version,
+            config_id: commitment.configID,
+        }
+    };
+    let player_points: Vec<CommitmentPoint> = commitments.iter().map(point).collect();
+    let range = team_commitment_range(&point(env.commitment()), &player_points)
+        .unwrap_or_else(|err| panic!("Invalid player commitments: {err}"));
+
     for i in 0..11 {
         let player = players[i].clone();
         let token_id = token_ids[i];
 
         let verifyJournal = VerifyJournal {
-            commitment: env.commitment().clone(),
+            commitment: commitments[i].clone(),
             owner,
+            playerContract: contracts[i],
+            tokenId: token_id,
+            playerCID: player
+                .compute_cid_format(format)
+                .unwrap_or_else(|err| panic!("Player cannot be encoded as {format}: {err}"))
+                .uri(),
+            jerseyVerified: verify_jersey,
+            jerseyNumber: player.committed_jersey(verify_jersey),
+            format: format.id(),
         };
 
         env::verify(VERIFY_CID_ID, &verifyJournal.abi_encode());
     }
 
+    // The team metadata references players by their canonical JSON CIDs whatever `format` the
+    // players were pinned with, so the team CID does not depend on it
+    let team = Team {
+        owner,
+        formation,
+        players: players.to_vec(),
+    };
+    assert!(team.has_distinct_players(), "Team lists the same player twice");
+    let average_rating = team
+        .check_rating_band(&band)
+        .unwrap_or_else(|err| panic!("Team is ineligible: {err}"));
+
+    let journal = TeamJournal {
+        commitment: env.into_commitment(),
+        teamCID: team.team_cid_digest(),
+        playerIds: token_ids,
+        playerContracts: contracts,
+        playerCommitments: commitments,
+        registryHash: team.registry_hash().into(),
+        minRating: band.min,
+        maxRating: band.max,
+        averageRating: average_rating,
+        minTimepoint: range.min,
+        maxTimepoint: range.max,
+        format: format.id(),
+    };
+
+    env::commit_slice(&journal.abi_encode());
+
     // let contract = Contract::new(PLAYER_CONTRACT_ADDRESS, &env);
 
     // let owner_call = IERC721::ownerOfCall {
@@ -68,11 +119,4 @@ fn main() {
     //     expected_cid == player_cid,
     //     "Player CID does not match on-chain data"
     // );
-
-    // let journal = Journal {
-    //     commitment: env.into_commitment(),
-    //     owner,
-    // };
-
-    // env::commit_slice(&journal.abi_encode());
 }
\ No newline at end of file